@@ -9,7 +9,7 @@ fn borders(fg: Color) -> Block<'static> {
         .style(Style::new().fg(fg))
 }
 
-fn main() {
+fn main() -> domtui::Result<()> {
     let mut builder = ScreenBuilder::new();
 
     let root_view = Stack::horizontal((
@@ -46,7 +46,7 @@ fn main() {
 
     let mut screen = builder.finish(root_view);
 
-    let mut terminal = domtui::setup_terminal();
-    domtui::default_event_loop(&mut terminal, &mut screen).unwrap();
-    domtui::restore_terminal(terminal);
+    let mut terminal = domtui::setup_terminal()?;
+    domtui::default_event_loop(&mut terminal, &mut screen)?;
+    domtui::restore_terminal(terminal)
 }
@@ -0,0 +1,142 @@
+//! ANSI escape sequences for writing styled terminal output.
+
+use std::fmt::Write as _;
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::views::CursorStyle;
+
+/// Parameters of SGR (Select Graphic Rendition) for the modifiers.
+const MODIFIER_PARAMS: &[(Modifier, u8)] = &[
+    (Modifier::BOLD, 1),
+    (Modifier::DIM, 2),
+    (Modifier::ITALIC, 3),
+    (Modifier::UNDERLINED, 4),
+    (Modifier::SLOW_BLINK, 5),
+    (Modifier::RAPID_BLINK, 6),
+    (Modifier::REVERSED, 7),
+    (Modifier::HIDDEN, 8),
+    (Modifier::CROSSED_OUT, 9),
+];
+
+/// The 16 named colors as indices of the 256-color palette.
+fn named_color_index(color: Color) -> Option<u8> {
+    Some(match color {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        _ => return None,
+    })
+}
+
+/// Write the SGR parameters of a color, `base` is `30` for foreground, `40` for background and
+/// `None` for underline color (which has no parameters for the 16 named colors).
+fn write_color_params(params: &mut String, color: Color, base: Option<u8>) {
+    let extended = base.map_or(58, |base| base + 8);
+    match (color, named_color_index(color), base) {
+        (Color::Reset, _, _) => (),
+        (_, Some(index @ 0..=7), Some(base)) => write!(params, ";{}", base + index).unwrap(),
+        (_, Some(index), Some(base)) => write!(params, ";{}", base + 60 + index - 8).unwrap(),
+        (_, Some(index), None) | (Color::Indexed(index), _, _) => {
+            write!(params, ";{extended};5;{index}").unwrap()
+        }
+        (Color::Rgb(r, g, b), _, _) => write!(params, ";{extended};2;{r};{g};{b}").unwrap(),
+        _ => (),
+    }
+}
+
+/// The escape sequence that resets all attributes, then sets those of `style`.
+pub(crate) fn sgr(style: Style) -> String {
+    let mut params = String::from("0");
+    write_color_params(&mut params, style.fg.unwrap_or(Color::Reset), Some(30));
+    write_color_params(&mut params, style.bg.unwrap_or(Color::Reset), Some(40));
+    write_color_params(
+        &mut params,
+        style.underline_color.unwrap_or(Color::Reset),
+        None,
+    );
+    for &(modifier, param) in MODIFIER_PARAMS {
+        if style.add_modifier.contains(modifier) {
+            write!(params, ";{param}").unwrap();
+        }
+    }
+    format!("\x1b[{params}m")
+}
+
+/// The escape sequence that resets all attributes.
+pub(crate) const RESET: &str = "\x1b[0m";
+
+/// The escape sequence that moves the cursor to a position (zero-based).
+pub(crate) fn move_to(x: u16, y: u16) -> String {
+    format!("\x1b[{};{}H", u32::from(y) + 1, u32::from(x) + 1)
+}
+
+/// The escape sequence (DECSCUSR) that sets the style of the cursor.
+pub(crate) fn cursor_style(cursor_style: CursorStyle) -> String {
+    let param = match cursor_style {
+        CursorStyle::Default => 0,
+        CursorStyle::BlinkingBlock => 1,
+        CursorStyle::SteadyBlock => 2,
+        CursorStyle::BlinkingUnderline => 3,
+        CursorStyle::SteadyUnderline => 4,
+        CursorStyle::BlinkingBar => 5,
+        CursorStyle::SteadyBar => 6,
+    };
+    format!("\x1b[{param} q")
+}
+
+pub(crate) const SHOW_CURSOR: &str = "\x1b[?25h";
+pub(crate) const HIDE_CURSOR: &str = "\x1b[?25l";
+pub(crate) const CLEAR: &str = "\x1b[2J";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sgr_of_colors_and_modifiers() {
+        assert_eq!(sgr(Style::new()), "\x1b[0m");
+        assert_eq!(sgr(Style::new().fg(Color::Red)), "\x1b[0;31m");
+        assert_eq!(sgr(Style::new().fg(Color::LightRed)), "\x1b[0;91m");
+        assert_eq!(sgr(Style::new().bg(Color::Blue)), "\x1b[0;44m");
+        assert_eq!(sgr(Style::new().bg(Color::White)), "\x1b[0;107m");
+        assert_eq!(
+            sgr(Style::new().fg(Color::Indexed(208))),
+            "\x1b[0;38;5;208m"
+        );
+        assert_eq!(
+            sgr(Style::new().bg(Color::Rgb(1, 2, 3))),
+            "\x1b[0;48;2;1;2;3m"
+        );
+        assert_eq!(
+            sgr(Style::new().underline_color(Color::Red)),
+            "\x1b[0;58;5;1m"
+        );
+        assert_eq!(
+            sgr(Style::new()
+                .fg(Color::Reset)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)),
+            "\x1b[0;1;4m"
+        );
+    }
+
+    #[test]
+    fn move_to_is_one_based() {
+        assert_eq!(move_to(0, 0), "\x1b[1;1H");
+        assert_eq!(move_to(4, 2), "\x1b[3;5H");
+        assert_eq!(move_to(u16::MAX, 0), "\x1b[1;65536H");
+    }
+}
@@ -0,0 +1,294 @@
+//! Recording of rendered frames into the asciicast v2 format of asciinema.
+//!
+//! `AsciicastBackend` wraps another backend, e.g. `CrosstermBackend` for recording while running,
+//! or `TestBackend` for recording headless, and writes everything drawn on it as output events of
+//! an asciicast.
+//!
+//! `EventLoop::run` and `EventLoop::run_async` read events from the real terminal, whatever the
+//! backend is. To record headless, e.g. in CI, turn that off with
+//! `EventLoop::read_terminal_events` and send the events with `ScreenHandle::send_event`, pass
+//! the events into `EventLoop::run_async_with`, or drive the screen yourself: pass events into it
+//! with `Screen::handle_event` (or replay a recording with `Replayer::replay`), and draw it with
+//! `Screen::render` on a terminal with an `AsciicastBackend` using a virtual clock, advancing the
+//! clock between frames.
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use ratatui::{
+    backend::{Backend, ClearType, WindowSize},
+    buffer::Cell,
+    layout::{Position, Size},
+    text::Span,
+};
+
+use crate::{ansi, terminal::CursorStyleBackend, views::CursorStyle};
+
+/// Where the timestamps of an asciicast come from.
+#[derive(Debug, Clone, Copy)]
+enum Clock {
+    /// Time elapsed since the start.
+    Real(Instant),
+    /// Time advanced manually, see `AsciicastBackend::advance`.
+    Virtual(Duration),
+}
+
+/// A backend that records everything drawn on the wrapped backend into an asciicast (v2).
+///
+/// The header of the asciicast is written on creation. Each time the terminal is flushed (once per
+/// `Screen::render`), what was drawn since the last flush is written as one output event. Size
+/// changes of the wrapped backend are written as resize events. Changes of the cursor style (see
+/// `CursorStyleBackend`) are recorded too, if the wrapped backend supports them.
+#[derive(Debug)]
+pub struct AsciicastBackend<B: Backend, W: Write> {
+    backend: B,
+    writer: W,
+    clock: Clock,
+    /// Output not written as an event yet.
+    output: String,
+    /// Size of the wrapped backend when last written.
+    size: Size,
+}
+
+impl<B: Backend, W: Write> AsciicastBackend<B, W> {
+    /// Wrap a backend, writing the header of the asciicast into `writer`.
+    /// Timestamps are real time elapsed since now.
+    pub fn new(backend: B, writer: W) -> io::Result<Self> {
+        Self::with_clock(backend, writer, Clock::Real(Instant::now()))
+    }
+
+    /// Like `AsciicastBackend::new`, but time only passes when calling `AsciicastBackend::advance`.
+    /// Useful for recording headless, where rendering takes no time.
+    pub fn with_virtual_clock(backend: B, writer: W) -> io::Result<Self> {
+        Self::with_clock(backend, writer, Clock::Virtual(Duration::ZERO))
+    }
+
+    fn with_clock(backend: B, mut writer: W, clock: Clock) -> io::Result<Self> {
+        let size = backend.size()?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        writeln!(
+            writer,
+            r#"{{"version": 2, "width": {}, "height": {}, "timestamp": {timestamp}}}"#,
+            size.width, size.height,
+        )?;
+        Ok(Self {
+            backend,
+            writer,
+            clock,
+            output: String::new(),
+            size,
+        })
+    }
+
+    /// Advance the virtual clock, see `AsciicastBackend::with_virtual_clock`.
+    /// No-op when recording in real time.
+    pub fn advance(&mut self, duration: Duration) {
+        if let Clock::Virtual(time) = &mut self.clock {
+            *time += duration;
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// The wrapped backend and the writer of the asciicast.
+    pub fn into_inner(self) -> (B, W) {
+        (self.backend, self.writer)
+    }
+
+    fn time(&self) -> Duration {
+        match self.clock {
+            Clock::Real(start) => start.elapsed(),
+            Clock::Virtual(time) => time,
+        }
+    }
+
+    /// Write an event of the asciicast.
+    fn write_event(&mut self, code: char, data: &str) -> io::Result<()> {
+        let time = self.time().as_secs_f64();
+        writeln!(
+            self.writer,
+            "[{time:.6}, \"{code}\", {}]",
+            json_string(data)
+        )?;
+        self.writer.flush()
+    }
+}
+
+/// Quote and escape a string as JSON.
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for char in s.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            char if char.is_control() => write!(json, "\\u{:04x}", char as u32).unwrap(),
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+    json
+}
+
+impl<B: Backend, W: Write> Backend for AsciicastBackend<B, W> {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let content: Vec<(u16, u16, &Cell)> = content.collect();
+        let mut next_position = None;
+        let mut style = None;
+        for &(x, y, cell) in &content {
+            if next_position != Some(Position::new(x, y)) {
+                self.output.push_str(&ansi::move_to(x, y));
+            }
+            let width = Span::raw(cell.symbol()).width().max(1) as u16;
+            next_position = Some(Position::new(x.saturating_add(width), y));
+            if style != Some(cell.style()) {
+                self.output.push_str(&ansi::sgr(cell.style()));
+                style = Some(cell.style());
+            }
+            self.output.push_str(cell.symbol());
+        }
+        if style.is_some() {
+            self.output.push_str(ansi::RESET);
+        }
+        self.backend.draw(content.into_iter())
+    }
+
+    fn append_lines(&mut self, n: u16) -> io::Result<()> {
+        self.output.push_str(&"\n".repeat(n.into()));
+        self.backend.append_lines(n)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.output.push_str(ansi::HIDE_CURSOR);
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.output.push_str(ansi::SHOW_CURSOR);
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        self.backend.get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+        let position = position.into();
+        self.output.push_str(&ansi::move_to(position.x, position.y));
+        self.backend.set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.output.push_str(ansi::CLEAR);
+        self.backend.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+        self.output.push_str(match clear_type {
+            ClearType::All => ansi::CLEAR,
+            ClearType::AfterCursor => "\x1b[J",
+            ClearType::BeforeCursor => "\x1b[1J",
+            ClearType::CurrentLine => "\x1b[2K",
+            ClearType::UntilNewLine => "\x1b[K",
+        });
+        self.backend.clear_region(clear_type)
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        self.backend.size()
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        self.backend.window_size()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let size = self.backend.size()?;
+        if size != self.size {
+            self.size = size;
+            self.write_event('r', &format!("{}x{}", size.width, size.height))?;
+        }
+        if !self.output.is_empty() {
+            let output = std::mem::take(&mut self.output);
+            self.write_event('o', &output)?;
+        }
+        self.backend.flush()
+    }
+}
+
+impl<B: CursorStyleBackend, W: Write> CursorStyleBackend for AsciicastBackend<B, W> {
+    fn set_cursor_style(&mut self, cursor_style: CursorStyle) -> io::Result<()> {
+        self.output.push_str(&ansi::cursor_style(cursor_style));
+        self.backend.set_cursor_style(cursor_style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{backend::TestBackend, style::Color, Terminal};
+
+    use super::*;
+    use crate::views::{Paragraph, Screen, Stack};
+
+    #[test]
+    fn records_screen() {
+        let screen = Screen::new(Stack::vertical((
+            Paragraph::new("a文").fg(Color::Red),
+            Paragraph::new("b").fg(Color::Yellow).bg(Color::Blue),
+        )));
+        let backend =
+            AsciicastBackend::with_virtual_clock(TestBackend::new(4, 2), Vec::new()).unwrap();
+        let mut terminal = Terminal::new(backend).unwrap();
+        screen.render(&mut terminal).unwrap();
+        terminal.backend_mut().advance(Duration::from_millis(1500));
+        terminal.backend_mut().backend_mut().resize(5, 2);
+        screen.render(&mut terminal).unwrap();
+        let cast = String::from_utf8(terminal.backend().writer().clone()).unwrap();
+        let mut lines = cast.lines();
+        let header = lines.next().unwrap();
+        assert!(header.starts_with(r#"{"version": 2, "width": 4, "height": 2, "timestamp": "#));
+        assert!(header.ends_with('}'));
+        assert_eq!(
+            lines.collect::<Vec<_>>(),
+            [
+                r#"[0.000000, "o", "\u001b[1;1H\u001b[0;31ma文 \u001b[2;1H\u001b[0;33;44mb   \u001b[0m\u001b[?25l"]"#,
+                r#"[1.500000, "r", "5x2"]"#,
+                r#"[1.500000, "o", "\u001b[2J\u001b[1;1H\u001b[0;31ma文  \u001b[2;1H\u001b[0;33;44mb    \u001b[0m\u001b[?25l"]"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn records_cursor_style() {
+        let mut backend =
+            AsciicastBackend::with_virtual_clock(TestBackend::new(4, 2), Vec::new()).unwrap();
+        backend.set_cursor_style(CursorStyle::SteadyBar).unwrap();
+        backend.advance(Duration::from_millis(500));
+        Backend::flush(&mut backend).unwrap();
+        let (_, cast) = backend.into_inner();
+        let cast = String::from_utf8(cast).unwrap();
+        let events: Vec<&str> = cast.lines().skip(1).collect();
+        assert_eq!(events, [r#"[0.500000, "o", "\u001b[6 q"]"#]);
+    }
+}
@@ -0,0 +1,137 @@
+//! Editing text in an external editor, like `<C-x C-e>` in bash.
+
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{self, Command},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::Result;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+/// The editor from `$VISUAL` or `$EDITOR`, e.g. `code --wait`.
+fn editor_from_env() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_owned())
+}
+
+/// The command that runs `editor` on the file at `path`.
+/// An editor that is the path of an existing file is run as is, so the path may contain spaces.
+/// Otherwise on Unix, the editor is run by `sh` like git does, so it may contain arguments and
+/// quotes, e.g. `"/opt/my editor/edit" --wait`. Elsewhere, it is split at whitespace.
+fn editor_command(editor: &str, path: &Path) -> Command {
+    let mut command;
+    if Path::new(editor).is_file() {
+        command = Command::new(editor);
+    } else if cfg!(unix) {
+        command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("{editor} \"$@\""))
+            .arg(editor);
+    } else {
+        let mut words = editor.split_whitespace();
+        command = Command::new(words.next().unwrap_or(DEFAULT_EDITOR));
+        command.args(words);
+    }
+    command.arg(path);
+    command
+}
+
+/// A temporary file that is removed when dropped.
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Create a new temporary file containing `contents`.
+    fn create(contents: &str) -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        loop {
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = env::temp_dir().join(format!("domtui-{}-{n}.txt", process::id()));
+            match File::create_new(&path) {
+                Ok(mut file) => {
+                    let temp_file = Self { path };
+                    file.write_all(contents.as_bytes())?;
+                    return Ok(temp_file);
+                }
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Edit `text` in the editor from `$VISUAL` or `$EDITOR` (`vi` if neither is set), see
+/// `edit_with`.
+pub fn edit(text: &str) -> Result<String> {
+    edit_with(&editor_from_env(), text)
+}
+
+/// Edit `text` in `editor`, e.g. `code --wait`, through a temporary file. Blocks until the editor
+/// exits, the terminal must be restored meanwhile (see `terminal::run_restored`).
+///
+/// `editor` is the path of the editor, or on Unix, a shell command that the path of the file is
+/// appended to, so paths with spaces need quotes only with arguments.
+/// A trailing line break added by the editor is removed, unless `text` ended with one.
+/// Fails if the editor can not be run or exits unsuccessfully.
+pub fn edit_with(editor: &str, text: &str) -> Result<String> {
+    let temp_file = TempFile::create(text)?;
+    let status = editor_command(editor, &temp_file.path).status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("editor `{editor}` exited with {status}")).into());
+    }
+    let mut edited = fs::read_to_string(&temp_file.path)?;
+    if !text.ends_with('\n') && edited.ends_with('\n') {
+        edited.pop();
+        if edited.ends_with('\r') {
+            edited.pop();
+        }
+    }
+    Ok(edited)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn edit_with_stub_editors() {
+        let dir = env::temp_dir().join(format!("domtui editor {}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("stub editor");
+        fs::write(&script, "#!/bin/sh\nprintf ' edited' >> \"$1\"\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let script = script.to_str().unwrap();
+
+        assert_eq!(edit_with(script, "text").unwrap(), "text edited");
+        assert_eq!(
+            edit_with(&format!("'{script}'"), "text").unwrap(),
+            "text edited"
+        );
+        let by_shell = "printf ' %s\\n' 'by shell' >>";
+        assert_eq!(edit_with(by_shell, "text").unwrap(), "text by shell");
+        assert_eq!(edit_with(by_shell, "text\n").unwrap(), "text\n by shell\n");
+        assert!(matches!(edit_with("false", "text"), Err(Error::Io(_))));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,58 @@
+//! The error type of domtui.
+
+use std::{
+    error,
+    fmt::{self, Display},
+    io,
+    sync::PoisonError,
+};
+
+/// Shorthand for results with `domtui::Error`.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Reading from or writing to the terminal (or another I/O resource) failed.
+    Io(io::Error),
+    /// Accessing the system clipboard failed.
+    Clipboard(Box<dyn error::Error + Send + Sync>),
+    /// A panic occurred while a lock was held, e.g. a panic in a method of a view wrapped in a
+    /// `ViewCell`. The state behind the lock may be inconsistent.
+    PoisonedLock,
+    /// The terminal was not set up by `TerminalBuilder`, but needs to be restored, e.g. by
+    /// `terminal::run_restored`.
+    TerminalNotSetUp,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "I/O error: {error}"),
+            Self::Clipboard(error) => write!(f, "clipboard error: {error}"),
+            Self::PoisonedLock => write!(f, "a lock was poisoned by a panic"),
+            Self::TerminalNotSetUp => write!(f, "the terminal was not set up by domtui"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Clipboard(error) => Some(error.as_ref()),
+            Self::PoisonedLock | Self::TerminalNotSetUp => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl<T> From<PoisonError<T>> for Error {
+    fn from(_: PoisonError<T>) -> Self {
+        Self::PoisonedLock
+    }
+}
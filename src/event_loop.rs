@@ -0,0 +1,774 @@
+//! Configurable event loop for running a `Screen`.
+
+use std::{
+    any::Any,
+    fmt::{self, Debug},
+    io,
+    ops::ControlFlow,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    time::{Duration, Instant},
+};
+
+use futures_core::Stream;
+use ratatui::{
+    backend::Backend,
+    crossterm::event::{Event, EventStream, KeyEventKind},
+    Terminal,
+};
+
+#[cfg(feature = "record")]
+use std::io::Write;
+
+#[cfg(feature = "record")]
+use crate::record::Recorder;
+use crate::{
+    editor,
+    handle::{ScreenHandle, ScreenMessage},
+    keymap::KeyChord,
+    terminal::{self, CursorStyleBackend},
+    views::{CursorStyle, EventResult, Screen, View},
+    Error, Result,
+};
+
+type Hook<'h, 'a, V> = Box<dyn FnMut(&mut Screen<'a, V>) + 'h>;
+type EventHandler<'h, 'a, V> =
+    Box<dyn FnMut(&mut Screen<'a, V>, &Event, EventResult) -> ControlFlow<()> + 'h>;
+type PostHandler<'h, 'a, V> =
+    Box<dyn FnMut(&mut Screen<'a, V>, Box<dyn Any + Send>) -> ControlFlow<()> + 'h>;
+type ErrorHandler<'h, 'a, V> = Box<dyn FnMut(&mut Screen<'a, V>, Error) + 'h>;
+type Editor<'h> = Box<dyn FnMut(&str) -> Result<String> + 'h>;
+
+/// Reads terminal events with crossterm's `EventStream`, which waits for them on a thread of its
+/// own and sends `ScreenMessage::EventsReady` to the screen when there are events to read, so the
+/// event loop can wait for terminal events and messages from `ScreenHandle`s at the same time.
+/// The thread is stopped when this is dropped.
+struct EventReader {
+    events: EventStream,
+    waker: Waker,
+}
+
+impl EventReader {
+    /// Start reading, the first `ScreenMessage::EventsReady` is sent right away.
+    fn spawn(handle: ScreenHandle) -> Self {
+        handle.send(ScreenMessage::EventsReady);
+        Self {
+            events: EventStream::new(),
+            waker: Arc::new(EventsReadyWaker(handle)).into(),
+        }
+    }
+
+    /// The next event read from the terminal, or `None` if there is none yet, in which case
+    /// `ScreenMessage::EventsReady` is sent when there is.
+    fn try_next(&mut self) -> Option<io::Result<Event>> {
+        let mut context = Context::from_waker(&self.waker);
+        match Pin::new(&mut self.events).poll_next(&mut context) {
+            Poll::Ready(event) => event,
+            Poll::Pending => None,
+        }
+    }
+}
+
+/// Wakes the event loop with `ScreenMessage::EventsReady`.
+struct EventsReadyWaker(ScreenHandle);
+
+impl Wake for EventsReadyWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.send(ScreenMessage::EventsReady);
+    }
+}
+
+/// An event loop that renders a `Screen` and passes terminal events into it.
+/// Configured in builder style, e.g. `EventLoop::new().tick_rate(...).run(terminal, screen)`.
+pub struct EventLoop<'h, 'a, V: View + 'a> {
+    quit_key: Option<KeyChord>,
+    suspend_key: Option<KeyChord>,
+    /// Set when the suspend key was pressed (or on `SIGTSTP`), the terminal is suspended before
+    /// the next render.
+    suspend_requested: bool,
+    /// Set on `SIGCONT`, the terminal is set up again before the next render.
+    resume_requested: bool,
+    tick_rate: Duration,
+    read_terminal_events: bool,
+    /// Reads terminal events while `EventLoop::run` is running.
+    event_reader: Option<EventReader>,
+    before_render: Option<Hook<'h, 'a, V>>,
+    after_render: Option<Hook<'h, 'a, V>>,
+    on_tick: Option<Hook<'h, 'a, V>>,
+    on_event: Option<EventHandler<'h, 'a, V>>,
+    on_post: Option<PostHandler<'h, 'a, V>>,
+    on_edit_error: Option<ErrorHandler<'h, 'a, V>>,
+    editor: Option<Editor<'h>>,
+    /// The cursor style last applied to the terminal.
+    cursor_style: CursorStyle,
+    #[cfg(feature = "record")]
+    recorder: Option<Recorder<Box<dyn Write + 'h>>>,
+}
+
+impl<'a, V: View + 'a> Default for EventLoop<'_, 'a, V> {
+    fn default() -> Self {
+        Self {
+            quit_key: "C-q".parse().ok(),
+            suspend_key: "C-z".parse().ok(),
+            suspend_requested: false,
+            resume_requested: false,
+            tick_rate: Duration::from_millis(100),
+            read_terminal_events: true,
+            event_reader: None,
+            before_render: None,
+            after_render: None,
+            on_tick: None,
+            on_event: None,
+            on_post: None,
+            on_edit_error: None,
+            editor: None,
+            cursor_style: CursorStyle::Default,
+            #[cfg(feature = "record")]
+            recorder: None,
+        }
+    }
+}
+
+impl<'h, 'a, V: View + 'a> EventLoop<'h, 'a, V> {
+    /// An event loop that quits on `<C-q>` and suspends on `<C-z>`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The key chord that ends the event loop, if the screen did not handle it.
+    /// Defaults to `<C-q>`, `None` for no quit key.
+    pub fn quit_key(mut self, quit_key: Option<KeyChord>) -> Self {
+        self.quit_key = quit_key;
+        self
+    }
+
+    /// The key chord that suspends the process (see `terminal::suspend`), if the screen did not
+    /// handle it. `SIGTSTP` suspends the same, even without a suspend key.
+    /// Defaults to `<C-z>`, `None` for no suspend key.
+    pub fn suspend_key(mut self, suspend_key: Option<KeyChord>) -> Self {
+        self.suspend_key = suspend_key;
+        self
+    }
+
+    /// How often `on_tick` is called.
+    /// Defaults to 100 ms. Without `on_tick`, the event loop does not tick, and sleeps until the
+    /// next event, message or timer of a view (see `MutView::on_tick`).
+    pub fn tick_rate(mut self, tick_rate: Duration) -> Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    /// Whether `EventLoop::run` and `EventLoop::run_async` read events from the terminal.
+    /// Defaults to `true`. Without reading from the terminal, the event loop only receives events
+    /// sent by `ScreenHandle::send_event`, so it can run headless, e.g. on a `TestBackend`.
+    pub fn read_terminal_events(mut self, read_terminal_events: bool) -> Self {
+        self.read_terminal_events = read_terminal_events;
+        self
+    }
+
+    /// Called before each render of the screen.
+    /// The screen is only rendered when it changed, see `Screen::needs_render`.
+    pub fn before_render(mut self, f: impl FnMut(&mut Screen<'a, V>) + 'h) -> Self {
+        self.before_render = Some(Box::new(f));
+        self
+    }
+
+    /// Called after each render of the screen.
+    pub fn after_render(mut self, f: impl FnMut(&mut Screen<'a, V>) + 'h) -> Self {
+        self.after_render = Some(Box::new(f));
+        self
+    }
+
+    /// Called once every tick, see `EventLoop::tick_rate`.
+    /// The screen is rendered after each tick.
+    pub fn on_tick(mut self, f: impl FnMut(&mut Screen<'a, V>) + 'h) -> Self {
+        self.on_tick = Some(Box::new(f));
+        self
+    }
+
+    /// Called for each event after the screen handled it, with whether the screen handled it.
+    /// Returning `ControlFlow::Break` ends the event loop.
+    pub fn on_event(
+        mut self,
+        f: impl FnMut(&mut Screen<'a, V>, &Event, EventResult) -> ControlFlow<()> + 'h,
+    ) -> Self {
+        self.on_event = Some(Box::new(f));
+        self
+    }
+
+    /// Called for each message posted by a `ScreenHandle` (see `ScreenHandle::post`).
+    /// Returning `ControlFlow::Break` ends the event loop.
+    pub fn on_post(
+        mut self,
+        f: impl FnMut(&mut Screen<'a, V>, Box<dyn Any + Send>) -> ControlFlow<()> + 'h,
+    ) -> Self {
+        self.on_post = Some(Box::new(f));
+        self
+    }
+
+    /// Called when the external editor (see `Action::EditExternally`) fails, e.g. because it was
+    /// not found or exited unsuccessfully, for showing the error to the user.
+    /// Without it, the error ends the event loop.
+    pub fn on_edit_error(mut self, f: impl FnMut(&mut Screen<'a, V>, Error) + 'h) -> Self {
+        self.on_edit_error = Some(Box::new(f));
+        self
+    }
+
+    /// Edits the text for `Action::EditExternally` instead of the editor from `$VISUAL` or
+    /// `$EDITOR`, e.g. with `editor::edit_with` for an editor of the application's choosing.
+    /// Unlike that editor, it is called without restoring the terminal, so it should open a window
+    /// of its own, or restore the terminal itself.
+    pub fn editor(mut self, f: impl FnMut(&str) -> Result<String> + 'h) -> Self {
+        self.editor = Some(Box::new(f));
+        self
+    }
+
+    /// Record the terminal size and all events into a writer, see the `record` module.
+    #[cfg(feature = "record")]
+    pub fn record(mut self, writer: impl Write + 'h) -> Self {
+        self.recorder = Some(Recorder::new(Box::new(writer)));
+        self
+    }
+
+    /// Record the current size of the terminal, if recording.
+    #[cfg(feature = "record")]
+    fn record_size<B: Backend>(&mut self, terminal: &Terminal<B>) -> Result<()> {
+        if let Some(recorder) = &mut self.recorder {
+            let size = terminal.size()?;
+            recorder.record_size(size.width, size.height)?;
+        }
+        Ok(())
+    }
+
+    /// If an event is a press of `key`.
+    fn is_key_press(event: &Event, key: Option<KeyChord>) -> bool {
+        match (event, key) {
+            (Event::Key(key_event), Some(key)) => {
+                key_event.kind == KeyEventKind::Press && KeyChord::from(*key_event) == key
+            }
+            _ => false,
+        }
+    }
+
+    /// Run the external editor if a view requested it (see `Screen::request_external_edit`), the
+    /// screen is rendered fully afterwards.
+    /// If the editor fails, the text of the view is left unchanged, and the error is passed to
+    /// `on_edit_error`, or returned without it.
+    fn edit_if_requested<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<'a, V>,
+    ) -> Result<()> {
+        if let Some(edit) = screen.take_external_edit() {
+            let edited = match &mut self.editor {
+                Some(editor) => editor(edit.text()),
+                None => terminal::run_restored(terminal, || editor::edit(edit.text()))?,
+            };
+            self.cursor_style = CursorStyle::Default;
+            screen.mark_dirty();
+            match (edited, &mut self.on_edit_error) {
+                (Ok(text), _) => edit.finish(text)?,
+                (Err(error), Some(on_edit_error)) => on_edit_error(screen, error),
+                (Err(error), None) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Suspend the terminal if requested, or set it up again if the process was continued, the
+    /// screen is rendered fully afterwards.
+    fn suspend_if_requested<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<'a, V>,
+    ) -> Result<()> {
+        let suspend = std::mem::take(&mut self.suspend_requested);
+        let resume = std::mem::take(&mut self.resume_requested);
+        if suspend {
+            terminal::suspend(terminal)?;
+        } else if resume {
+            terminal::resume(terminal)?;
+        }
+        if suspend || resume {
+            self.cursor_style = CursorStyle::Default;
+            screen.mark_dirty();
+        }
+        Ok(())
+    }
+
+    /// Call `on_tick`.
+    fn tick(&mut self, screen: &mut Screen<'a, V>) {
+        if let Some(on_tick) = &mut self.on_tick {
+            on_tick(screen);
+            screen.mark_dirty();
+        }
+    }
+
+    /// Handle key timeouts and timers, then render the screen if it changed, calling
+    /// `before_render` and `after_render`.
+    fn render<B: CursorStyleBackend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<'a, V>,
+    ) -> Result<()> {
+        screen.handle_key_timeout();
+        screen.handle_timers();
+        if !screen.needs_render() {
+            return Ok(());
+        }
+        if let Some(before_render) = &mut self.before_render {
+            before_render(screen);
+        }
+        screen.render(terminal)?;
+        let cursor_style = screen.cursor_style();
+        if cursor_style != self.cursor_style {
+            terminal.backend_mut().set_cursor_style(cursor_style)?;
+            terminal.backend_mut().flush()?;
+            self.cursor_style = cursor_style;
+        }
+        if let Some(after_render) = &mut self.after_render {
+            after_render(screen);
+        }
+        Ok(())
+    }
+
+    /// Pass an event into the screen, then `on_event`.
+    /// Returns `ControlFlow::Break` if the event loop should end.
+    fn handle_event(
+        &mut self,
+        screen: &mut Screen<'a, V>,
+        event: Event,
+    ) -> Result<ControlFlow<()>> {
+        #[cfg(feature = "record")]
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record_event(&event)?;
+        }
+        let result = screen.handle_event(event.clone());
+        if result.is_ignored() {
+            if Self::is_key_press(&event, self.quit_key) {
+                return Ok(ControlFlow::Break(()));
+            }
+            if Self::is_key_press(&event, self.suspend_key) {
+                self.suspend_requested = true;
+            }
+        }
+        Ok(match &mut self.on_event {
+            Some(on_event) => on_event(screen, &event, result),
+            None => ControlFlow::Continue(()),
+        })
+    }
+
+    /// Handle a message from a `ScreenHandle` (or the `EventReader`, or a signal).
+    /// Returns `ControlFlow::Break` if the event loop should end.
+    fn handle_message(
+        &mut self,
+        screen: &mut Screen<'a, V>,
+        message: ScreenMessage,
+    ) -> Result<ControlFlow<()>> {
+        match message {
+            ScreenMessage::Update { tag, update } => screen.apply_update(&tag, update),
+            ScreenMessage::SetTimer { tag, timer } => {
+                screen.set_timer_tagged(&tag, timer);
+            }
+            ScreenMessage::Post(post) => {
+                if let Some(on_post) = &mut self.on_post {
+                    return Ok(on_post(screen, post));
+                }
+            }
+            ScreenMessage::Event(event) => return self.handle_event(screen, event),
+            ScreenMessage::EventsReady => return self.read_events(screen),
+            ScreenMessage::Wake => screen.mark_dirty(),
+            ScreenMessage::Suspend => self.suspend_requested = true,
+            ScreenMessage::Continued => self.resume_requested = true,
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Pass the events read from the terminal so far into the screen, see `EventReader`.
+    /// Stops early if the terminal is to be suspended or handed to an external editor, leaving
+    /// the rest of the input to the shell or the editor.
+    /// Returns `ControlFlow::Break` if the event loop should end.
+    fn read_events(&mut self, screen: &mut Screen<'a, V>) -> Result<ControlFlow<()>> {
+        while !self.suspend_requested && !screen.has_external_edit() {
+            let Some(event) = self.event_reader.as_mut().and_then(EventReader::try_next) else {
+                break;
+            };
+            if self.handle_event(screen, event?)?.is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Start reading terminal events for `EventLoop::run`, unless turned off with
+    /// `EventLoop::read_terminal_events`.
+    fn start_reading(&mut self, screen: &Screen<'a, V>) {
+        if self.read_terminal_events {
+            self.event_reader = Some(EventReader::spawn(screen.handle()));
+        }
+    }
+
+    /// Handle all queued messages from `ScreenHandle`s, starting with `first`.
+    /// Returns `ControlFlow::Break` if the event loop should end.
+    fn handle_messages(
+        &mut self,
+        screen: &mut Screen<'a, V>,
+        first: Option<ScreenMessage>,
+    ) -> Result<ControlFlow<()>> {
+        let mut message = first.or_else(|| screen.try_recv_message());
+        while let Some(message_) = message {
+            if self.handle_message(screen, message_)?.is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+            message = screen.try_recv_message();
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Run the event loop until the quit key is pressed or `on_event` breaks.
+    /// Terminal events are waited for on a separate thread while the event loop is running.
+    /// They are read from the terminal whatever the backend of `terminal` is, see
+    /// `EventLoop::read_terminal_events` for running headless.
+    pub fn run<B: CursorStyleBackend>(
+        mut self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<'a, V>,
+    ) -> Result<()> {
+        #[cfg(feature = "record")]
+        self.record_size(terminal)?;
+        #[cfg(unix)]
+        let _signals = terminal::forward_signals(screen.handle());
+        self.start_reading(screen);
+        let mut next_tick = self
+            .on_tick
+            .is_some()
+            .then(|| Instant::now() + self.tick_rate);
+        loop {
+            let now = Instant::now();
+            if let Some(tick) = &mut next_tick {
+                if now >= *tick {
+                    *tick = now + self.tick_rate;
+                    self.tick(screen);
+                }
+            }
+            if self.suspend_requested || self.resume_requested || screen.has_external_edit() {
+                // The reader thread would compete with the shell or the editor for input.
+                self.event_reader = None;
+                self.suspend_if_requested(terminal, screen)?;
+                self.edit_if_requested(terminal, screen)?;
+                self.start_reading(screen);
+            }
+            self.render(terminal, screen)?;
+            let deadline = [
+                next_tick,
+                screen.key_timeout_deadline(),
+                screen.timer_deadline(),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            let Some(message) = screen.recv_message(deadline) else {
+                continue;
+            };
+            if self.handle_messages(screen, Some(message))?.is_break() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Like `EventLoop::run`, but asynchronous, reading terminal events from crossterm's
+    /// `EventStream`.
+    #[cfg(feature = "tokio")]
+    pub async fn run_async<B: CursorStyleBackend>(
+        self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<'a, V>,
+    ) -> Result<()> {
+        use futures::{future::Either, stream};
+
+        let events = match self.read_terminal_events {
+            true => Either::Left(EventStream::new()),
+            false => Either::Right(stream::pending()),
+        };
+        let messages = stream::pending::<!>();
+        self.run_async_with(terminal, screen, events, messages, |_, message| message)
+            .await
+    }
+
+    /// Like `EventLoop::run_async`, but reads terminal events from `events`, and also receives
+    /// messages from the application (e.g. results of background tasks) from `messages`, which are
+    /// passed into `on_message`.
+    /// The event loop ends when the quit key is pressed, when `on_event`, `on_post` or
+    /// `on_message` breaks, or when `events` ends.
+    /// `events` is not polled while suspended or running an external editor, but may still read
+    /// from the terminal then. crossterm's `EventStream` does until it has read one event, so
+    /// the first key typed into an external editor can get lost.
+    #[cfg(feature = "tokio")]
+    pub async fn run_async_with<B: CursorStyleBackend, M>(
+        mut self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<'a, V>,
+        events: impl futures::Stream<Item = std::io::Result<Event>>,
+        messages: impl futures::Stream<Item = M>,
+        mut on_message: impl FnMut(&mut Screen<'a, V>, M) -> ControlFlow<()>,
+    ) -> Result<()> {
+        use futures::StreamExt as _;
+        use tokio::time::{self, MissedTickBehavior};
+
+        #[cfg(feature = "record")]
+        self.record_size(terminal)?;
+        #[cfg(unix)]
+        let _signals = terminal::forward_signals(screen.handle());
+        let wakeup = screen.wakeup();
+        let mut events = std::pin::pin!(events);
+        let mut messages = std::pin::pin!(messages);
+        let mut messages_ended = false;
+        let mut ticks = time::interval_at(time::Instant::now() + self.tick_rate, self.tick_rate);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let ticking = self.on_tick.is_some();
+        loop {
+            if self.handle_messages(screen, None)?.is_break() {
+                return Ok(());
+            }
+            self.suspend_if_requested(terminal, screen)?;
+            self.edit_if_requested(terminal, screen)?;
+            self.render(terminal, screen)?;
+            let key_timeout = screen.key_timeout_deadline();
+            let timer_deadline = screen.timer_deadline();
+            let control_flow = tokio::select! {
+                _ = ticks.tick(), if ticking => {
+                    self.tick(screen);
+                    ControlFlow::Continue(())
+                }
+                _ = time::sleep_until(key_timeout.unwrap_or_else(Instant::now).into()),
+                    if key_timeout.is_some() => ControlFlow::Continue(()),
+                _ = time::sleep_until(timer_deadline.unwrap_or_else(Instant::now).into()),
+                    if timer_deadline.is_some() => ControlFlow::Continue(()),
+                _ = wakeup.notify.notified() => self.handle_messages(screen, None)?,
+                event = events.next() => match event {
+                    Some(event) => self.handle_event(screen, event?)?,
+                    None => ControlFlow::Break(()),
+                },
+                message = messages.next(), if !messages_ended => match message {
+                    Some(message) => on_message(screen, message),
+                    None => {
+                        messages_ended = true;
+                        ControlFlow::Continue(())
+                    }
+                },
+            };
+            if control_flow.is_break() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<'a, V: View + 'a> Debug for EventLoop<'_, 'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventLoop")
+            .field("quit_key", &self.quit_key)
+            .field("suspend_key", &self.suspend_key)
+            .field("tick_rate", &self.tick_rate)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    #[cfg(feature = "tokio")]
+    use futures::stream;
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+    use crate::{
+        testing::key_events,
+        views::{Empty, InputField, ScreenBuilder, ViewCell},
+    };
+    #[cfg(feature = "tokio")]
+    use crate::{testing::plain_dump, views::Action};
+
+    #[test]
+    fn run_handles_events_sent_from_other_threads() {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(InputField::default());
+        builder.initial_focus(&input);
+        let mut screen = builder.finish(input.clone());
+        let mut terminal = Terminal::new(TestBackend::new(8, 1)).unwrap();
+        let handle = screen.handle();
+        let sender = thread::spawn(move || {
+            for event in key_events("a b C-q c").unwrap() {
+                handle.send_event(event);
+            }
+        });
+        EventLoop::new()
+            .read_terminal_events(false)
+            .run(&mut terminal, &mut screen)
+            .unwrap();
+        sender.join().unwrap();
+        let text = unsafe {
+            ViewCell::inspect(&input, |field: &mut InputField| {
+                field.content().text().to_owned()
+            })
+        };
+        assert_eq!(text.unwrap(), "ab");
+    }
+
+    #[test]
+    fn run_ticks_until_on_post_breaks() {
+        let mut screen = Screen::new(Empty);
+        let mut terminal = Terminal::new(TestBackend::new(8, 1)).unwrap();
+        let mut ticks = 0;
+        EventLoop::new()
+            .read_terminal_events(false)
+            .tick_rate(Duration::from_millis(1))
+            .on_tick(|screen| {
+                ticks += 1;
+                if ticks == 3 {
+                    screen.handle().post(ticks);
+                }
+            })
+            .on_post(|_, post| match post.downcast::<i32>() {
+                Ok(_) => ControlFlow::Break(()),
+                Err(_) => ControlFlow::Continue(()),
+            })
+            .run(&mut terminal, &mut screen)
+            .unwrap();
+        assert_eq!(ticks, 3);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn run_async_with_quits_on_quit_key() {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(InputField::default());
+        builder.initial_focus(&input);
+        let mut screen = builder.finish(input);
+        let mut terminal = Terminal::new(TestBackend::new(8, 1)).unwrap();
+        let events = key_events("a b C-q c").unwrap().into_iter().map(Ok);
+        EventLoop::new()
+            .run_async_with(
+                &mut terminal,
+                &mut screen,
+                stream::iter(events),
+                stream::pending::<!>(),
+                |_, message| message,
+            )
+            .await
+            .unwrap();
+        assert_eq!(plain_dump(terminal.backend().buffer()), "ab\n");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn run_async_with_delivers_messages() {
+        let mut screen = Screen::new(Empty);
+        let mut terminal = Terminal::new(TestBackend::new(8, 1)).unwrap();
+        let mut received = Vec::new();
+        EventLoop::new()
+            .run_async_with(
+                &mut terminal,
+                &mut screen,
+                stream::pending(),
+                stream::iter(1..=3),
+                |_, message| {
+                    received.push(message);
+                    match message {
+                        3 => ControlFlow::Break(()),
+                        _ => ControlFlow::Continue(()),
+                    }
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(received, [1, 2, 3]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn run_async_with_ends_with_events() {
+        let mut screen = Screen::new(Empty);
+        let mut terminal = Terminal::new(TestBackend::new(8, 1)).unwrap();
+        screen.handle().post(42);
+        let mut posts = Vec::new();
+        EventLoop::new()
+            .on_post(|_, post| {
+                posts.extend(post.downcast::<i32>().ok().map(|post| *post));
+                ControlFlow::Continue(())
+            })
+            .run_async_with(
+                &mut terminal,
+                &mut screen,
+                stream::empty(),
+                stream::pending::<!>(),
+                |_, message| message,
+            )
+            .await
+            .unwrap();
+        assert_eq!(posts, [42]);
+    }
+
+    #[cfg(feature = "tokio")]
+    /// Type `keys` into a focused `InputField` containing `"text"`, with `C-x C-e` bound to
+    /// `Action::EditExternally` and edited by `editor`, returning how the event loop ended and the
+    /// shown text.
+    async fn edit_externally(
+        keys: &str,
+        editor: Option<fn(&str) -> Result<String>>,
+        on_edit_error: bool,
+    ) -> (Result<()>, String) {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(InputField::default().text("text").cursor_at_end());
+        builder.initial_focus(&input);
+        let mut screen = builder.finish(input);
+        screen
+            .bind_action("C-x C-e", Action::EditExternally)
+            .unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(16, 1)).unwrap();
+        let events = key_events(keys).unwrap().into_iter().map(Ok);
+        let mut event_loop = EventLoop::new();
+        if let Some(editor) = editor {
+            event_loop = event_loop.editor(editor);
+        }
+        if on_edit_error {
+            event_loop = event_loop.on_edit_error(|_, _| ());
+        }
+        let result = event_loop
+            .run_async_with(
+                &mut terminal,
+                &mut screen,
+                stream::iter(events),
+                stream::pending::<!>(),
+                |_, message| message,
+            )
+            .await;
+        (result, plain_dump(terminal.backend().buffer()))
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn edit_externally_with_stub_editor() {
+        let (result, text) = edit_externally(
+            "C-x C-e !",
+            Some(|text| Ok(format!("{text} edited"))),
+            false,
+        )
+        .await;
+        result.unwrap();
+        assert_eq!(text, "text edited!\n");
+
+        let fail: fn(&str) -> Result<String> = |_| Err(io::Error::other("failed").into());
+        let (result, text) = edit_externally("C-x C-e !", Some(fail), true).await;
+        result.unwrap();
+        assert_eq!(text, "text!\n");
+        let (result, _) = edit_externally("C-x C-e !", Some(fail), false).await;
+        assert!(matches!(result, Err(Error::Io(_))));
+
+        // The editor from `$VISUAL` or `$EDITOR` needs a terminal to restore.
+        let (result, _) = edit_externally("C-x C-e !", None, true).await;
+        assert!(matches!(result, Err(Error::TerminalNotSetUp)));
+    }
+}
@@ -0,0 +1,288 @@
+//! Exporting rendered screens, as text with ANSI escape sequences or as HTML.
+//!
+//! Both take the `Buffer` a `Screen` was rendered into, e.g. from `TestScreen::render` or
+//! `TestBackend::buffer`.
+
+use std::fmt::Write as _;
+
+use ratatui::{
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier},
+    text::Span,
+};
+
+use crate::ansi;
+
+/// Foreground color of HTML exports for `Color::Reset`.
+const DEFAULT_FG: (u8, u8, u8) = (0xe5, 0xe5, 0xe5);
+/// Background color of HTML exports for `Color::Reset`.
+const DEFAULT_BG: (u8, u8, u8) = (0x00, 0x00, 0x00);
+
+/// The 16 named colors, as in xterm.
+const NAMED_COLORS: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+/// Cells of each row of a buffer, skipping cells hidden behind wide characters.
+pub(crate) fn rows(buffer: &Buffer) -> impl Iterator<Item = Vec<(u16, &Cell)>> {
+    let area = buffer.area;
+    (area.top()..area.bottom()).map(move |y| {
+        let mut hidden = 0;
+        let mut row = Vec::new();
+        for x in area.left()..area.right() {
+            let cell = &buffer[(x, y)];
+            if hidden > 0 {
+                hidden -= 1;
+                continue;
+            }
+            hidden = Span::raw(cell.symbol()).width().saturating_sub(1);
+            row.push((x, cell));
+        }
+        row
+    })
+}
+
+/// The buffer as text with ANSI escape sequences for colors and modifiers, one line per row.
+/// Attributes are reset at the end of each line, so lines can be printed on their own.
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut ansi = String::new();
+    for row in rows(buffer) {
+        let mut style = None;
+        for (_, cell) in row {
+            if style != Some(cell.style()) {
+                style = Some(cell.style());
+                ansi.push_str(&ansi::sgr(cell.style()));
+            }
+            ansi.push_str(cell.symbol());
+        }
+        ansi.push_str(ansi::RESET);
+        ansi.push('\n');
+    }
+    ansi
+}
+
+/// RGB of a color, in the xterm palette for named and indexed colors.
+fn rgb(color: Color, reset: (u8, u8, u8)) -> (u8, u8, u8) {
+    let index = match color {
+        Color::Reset => return reset,
+        Color::Rgb(r, g, b) => return (r, g, b),
+        Color::Indexed(index) => index,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+    };
+    match index {
+        0..=15 => NAMED_COLORS[usize::from(index)],
+        // 6x6x6 color cube.
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        // Grayscale ramp.
+        232..=255 => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Inline CSS for the style of a cell.
+fn css(cell: &Cell) -> String {
+    let mut fg = rgb(cell.fg, DEFAULT_FG);
+    let mut bg = rgb(cell.bg, DEFAULT_BG);
+    if cell.modifier.contains(Modifier::REVERSED) {
+        (fg, bg) = (bg, fg);
+    }
+    let mut css = String::new();
+    if fg != DEFAULT_FG {
+        write!(css, "color:#{:02x}{:02x}{:02x};", fg.0, fg.1, fg.2).unwrap();
+    }
+    if bg != DEFAULT_BG {
+        write!(
+            css,
+            "background-color:#{:02x}{:02x}{:02x};",
+            bg.0, bg.1, bg.2
+        )
+        .unwrap();
+    }
+    if cell.modifier.contains(Modifier::BOLD) {
+        css.push_str("font-weight:bold;");
+    }
+    if cell.modifier.contains(Modifier::DIM) {
+        css.push_str("opacity:0.5;");
+    }
+    if cell.modifier.contains(Modifier::ITALIC) {
+        css.push_str("font-style:italic;");
+    }
+    let decorations: Vec<&str> = [
+        (Modifier::UNDERLINED, "underline"),
+        (Modifier::CROSSED_OUT, "line-through"),
+    ]
+    .into_iter()
+    .filter(|&(modifier, _)| cell.modifier.contains(modifier))
+    .map(|(_, decoration)| decoration)
+    .collect();
+    if !decorations.is_empty() {
+        write!(css, "text-decoration:{};", decorations.join(" ")).unwrap();
+        if cell.underline_color != Color::Reset {
+            let (r, g, b) = rgb(cell.underline_color, DEFAULT_FG);
+            write!(css, "text-decoration-color:#{r:02x}{g:02x}{b:02x};").unwrap();
+        }
+    }
+    if cell.modifier.contains(Modifier::HIDDEN) {
+        css.push_str("visibility:hidden;");
+    }
+    css
+}
+
+fn escape_html(s: &str, html: &mut String) {
+    for char in s.chars() {
+        match char {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            char => html.push(char),
+        }
+    }
+}
+
+/// The buffer as a self-contained HTML `<pre>` element, with styles inline.
+/// Default colors (`Color::Reset`) are light gray on black.
+pub fn to_html(buffer: &Buffer) -> String {
+    let mut html = format!(
+        "<pre style=\"font-family:monospace;line-height:1.2;color:#{:02x}{:02x}{:02x};\
+         background-color:#{:02x}{:02x}{:02x};padding:0.5em\">",
+        DEFAULT_FG.0, DEFAULT_FG.1, DEFAULT_FG.2, DEFAULT_BG.0, DEFAULT_BG.1, DEFAULT_BG.2,
+    );
+    for (y, row) in rows(buffer).enumerate() {
+        if y > 0 {
+            html.push('\n');
+        }
+        // Runs of cells of the same style, as `(css, text)`.
+        let mut runs: Vec<(String, String)> = Vec::new();
+        for (_, cell) in row {
+            let css = css(cell);
+            match runs.last_mut() {
+                Some((run_css, text)) if *run_css == css => text.push_str(cell.symbol()),
+                _ => runs.push((css, cell.symbol().to_owned())),
+            }
+        }
+        for (css, text) in runs {
+            if css.is_empty() {
+                escape_html(&text, &mut html);
+            } else {
+                write!(html, "<span style=\"{css}\">").unwrap();
+                escape_html(&text, &mut html);
+                html.push_str("</span>");
+            }
+        }
+    }
+    html.push_str("</pre>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{layout::Rect, style::Style};
+
+    use super::*;
+
+    fn buffer(lines: &[(&str, Style)]) -> Buffer {
+        let width = lines
+            .iter()
+            .map(|(line, _)| Span::raw(*line).width() as u16)
+            .max()
+            .unwrap_or(0);
+        let mut buffer = Buffer::empty(Rect::new(0, 0, width, lines.len() as u16));
+        for (y, (line, style)) in lines.iter().enumerate() {
+            buffer.set_string(0, y as u16, line, *style);
+        }
+        buffer
+    }
+
+    #[test]
+    fn ansi_merges_style_runs() {
+        let mut buffer = buffer(&[("ab c", Style::new()), ("文x", Style::new())]);
+        buffer.set_style(Rect::new(0, 0, 2, 1), Style::new().fg(Color::Red));
+        buffer.set_style(Rect::new(2, 1, 1, 1), Style::new().bg(Color::Blue));
+        assert_eq!(
+            to_ansi(&buffer),
+            "\x1b[0;31mab\x1b[0m c\x1b[0m\n\x1b[0m文\x1b[0;44mx\x1b[0m \x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn html_escapes_text() {
+        let buffer = buffer(&[("<a href=\"x\">&</a>", Style::new())]);
+        assert_eq!(
+            to_html(&buffer),
+            "<pre style=\"font-family:monospace;line-height:1.2;color:#e5e5e5;\
+             background-color:#000000;padding:0.5em\">\
+             &lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;</pre>"
+        );
+    }
+
+    #[test]
+    fn html_merges_style_runs() {
+        let mut buffer = buffer(&[("文ab", Style::new()), ("cd", Style::new())]);
+        buffer.set_style(
+            Rect::new(0, 0, 3, 1),
+            Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+        );
+        buffer.set_style(Rect::new(0, 1, 1, 1), Style::new().bg(Color::Indexed(196)));
+        buffer.set_style(
+            Rect::new(1, 1, 1, 1),
+            Style::new()
+                .fg(Color::Rgb(1, 2, 3))
+                .add_modifier(Modifier::REVERSED | Modifier::UNDERLINED),
+        );
+        let html = to_html(&buffer);
+        let body = &html[html.find('>').unwrap() + 1..html.len() - "</pre>".len()];
+        assert_eq!(
+            body,
+            "<span style=\"color:#cd0000;font-weight:bold;\">文a</span>b\
+             \n<span style=\"background-color:#ff0000;\">c</span>\
+             <span style=\"color:#000000;background-color:#010203;\
+             text-decoration:underline;\">d</span>  "
+        );
+    }
+
+    #[test]
+    fn rgb_of_indexed_colors() {
+        assert_eq!(rgb(Color::Indexed(9), DEFAULT_FG), (0xff, 0x00, 0x00));
+        assert_eq!(rgb(Color::Indexed(16), DEFAULT_FG), (0, 0, 0));
+        assert_eq!(rgb(Color::Indexed(231), DEFAULT_FG), (0xff, 0xff, 0xff));
+        assert_eq!(rgb(Color::Indexed(232), DEFAULT_FG), (8, 8, 8));
+        assert_eq!(rgb(Color::Reset, DEFAULT_BG), DEFAULT_BG);
+    }
+}
@@ -0,0 +1,215 @@
+//! Handles for updating a `Screen` from other threads.
+
+use std::{
+    any::Any,
+    fmt::{self, Debug},
+    sync::{mpsc, Arc},
+};
+
+use ratatui::crossterm::event::Event;
+
+use crate::views::{MutView, Timer};
+
+/// A closure run against a tagged view, see `ScreenHandle::update_tagged`.
+pub(crate) type Update = Box<dyn FnOnce(&mut dyn MutView) + Send>;
+
+/// Messages sent from `ScreenHandle`s to the `Screen`.
+pub(crate) enum ScreenMessage {
+    /// Run a closure against the view of a tag.
+    Update { tag: String, update: Update },
+    /// Start, replace or stop the timer of the view of a tag.
+    SetTimer { tag: String, timer: Option<Timer> },
+    /// A message for the application, see `ScreenHandle::post`.
+    Post(Box<dyn Any + Send>),
+    /// An event sent by `ScreenHandle::send_event`.
+    Event(Event),
+    /// Terminal events are ready to be read by the `EventReader` of `EventLoop`.
+    EventsReady,
+    /// Nothing, only wake the event loop.
+    Wake,
+    /// The process received `SIGTSTP`, the event loop suspends like on its suspend key.
+    Suspend,
+    /// The process was continued with `SIGCONT`, the event loop sets up the terminal again.
+    Continued,
+}
+
+impl Debug for ScreenMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Update { tag, update: _ } => f
+                .debug_struct("Update")
+                .field("tag", tag)
+                .finish_non_exhaustive(),
+            Self::SetTimer { tag, timer } => f
+                .debug_struct("SetTimer")
+                .field("tag", tag)
+                .field("timer", timer)
+                .finish(),
+            Self::Post(_) => f.debug_tuple("Post").finish_non_exhaustive(),
+            Self::Event(event) => f.debug_tuple("Event").field(event).finish(),
+            Self::EventsReady => f.debug_tuple("EventsReady").finish(),
+            Self::Wake => f.debug_tuple("Wake").finish(),
+            Self::Suspend => f.debug_tuple("Suspend").finish(),
+            Self::Continued => f.debug_tuple("Continued").finish(),
+        }
+    }
+}
+
+/// Wakes up an asynchronous event loop waiting on it.
+#[derive(Debug, Default)]
+pub(crate) struct Wakeup {
+    #[cfg(feature = "tokio")]
+    pub(crate) notify: tokio::sync::Notify,
+}
+
+impl Wakeup {
+    fn wake(&self) {
+        #[cfg(feature = "tokio")]
+        self.notify.notify_one();
+    }
+}
+
+/// A cloneable handle to a `Screen` that can be sent to other threads, for updating views and
+/// waking up the event loop.
+/// Created by `Screen::handle`.
+///
+/// Updates and messages are queued, and are processed by the event loop (or
+/// `Screen::process_messages`) on the thread that runs the screen, which then redraws.
+/// All functions return `false` if the screen no longer exists.
+#[derive(Debug, Clone)]
+pub struct ScreenHandle {
+    sender: mpsc::Sender<ScreenMessage>,
+    wakeup: Arc<Wakeup>,
+}
+
+impl ScreenHandle {
+    pub(crate) fn new(sender: mpsc::Sender<ScreenMessage>, wakeup: Arc<Wakeup>) -> Self {
+        Self { sender, wakeup }
+    }
+
+    pub(crate) fn send(&self, message: ScreenMessage) -> bool {
+        let is_sent = self.sender.send(message).is_ok();
+        self.wakeup.wake();
+        is_sent
+    }
+
+    /// Queue a closure to be run against the view of a tag.
+    /// If multiple views were tagged the same, one of them is updated, randomly.
+    /// If no view of such tag exists when the closure is due, the closure is dropped.
+    pub fn update_tagged(
+        &self,
+        tag: impl Into<String>,
+        update: impl FnOnce(&mut dyn MutView) + Send + 'static,
+    ) -> bool {
+        self.send(ScreenMessage::Update {
+            tag: tag.into(),
+            update: Box::new(update),
+        })
+    }
+
+    /// Like `update_tagged`, but downcasts the view into a value of concrete type.
+    ///
+    /// # Safety
+    /// `MV` must be of the correct type that the view of the tag was initialized with.
+    pub unsafe fn update_tagged_unchecked<MV: MutView>(
+        &self,
+        tag: impl Into<String>,
+        update: impl FnOnce(&mut MV) + Send + 'static,
+    ) -> bool {
+        self.update_tagged(tag, move |view: &mut dyn MutView| {
+            let view: &mut MV = unsafe { &mut *(view as *mut dyn MutView as *mut MV) };
+            update(view)
+        })
+    }
+
+    /// Queue starting, replacing, or with `None`, stopping the timer of the view of a tag, see
+    /// `Screen::set_timer`.
+    /// Views do not need this for their own timers, they can set them in their event hooks (see
+    /// `MutView::on_focus`).
+    /// If multiple views were tagged the same, the timer of one of them is set, randomly.
+    pub fn set_timer_tagged(&self, tag: impl Into<String>, timer: Option<Timer>) -> bool {
+        self.send(ScreenMessage::SetTimer {
+            tag: tag.into(),
+            timer,
+        })
+    }
+
+    /// Post a message to the application, received by `EventLoop::on_post` (or returned from
+    /// `Screen::process_messages`).
+    pub fn post(&self, message: impl Any + Send) -> bool {
+        self.send(ScreenMessage::Post(Box::new(message)))
+    }
+
+    /// Queue an event to be passed into the screen, as if it was read from the terminal.
+    /// Useful for running `EventLoop` headless, see `EventLoop::read_terminal_events`.
+    pub fn send_event(&self, event: Event) -> bool {
+        self.send(ScreenMessage::Event(event))
+    }
+
+    /// Wake up the event loop to redraw, e.g. after mutating a `ViewCell` directly.
+    pub fn wake(&self) -> bool {
+        self.send(ScreenMessage::Wake)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{
+        testing::TestScreen,
+        views::{Empty, InputField, Screen, ScreenBuilder, ViewCell},
+    };
+
+    #[test]
+    fn update_tagged_from_other_thread_marks_screen_dirty() {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.tagged_view_cell("input", InputField::default());
+        let mut test = TestScreen::new(builder.finish(input.clone()), 20, 1);
+        test.render();
+        let handle = test.screen().handle();
+        thread::spawn(move || {
+            handle.update_tagged("input", |view| {
+                let content = view.input_field_content_mut().unwrap();
+                content.set_text("updated".into());
+            })
+        })
+        .join()
+        .unwrap();
+        assert!(!test.screen().needs_render());
+        test.screen_mut().process_messages();
+        assert!(test.screen().needs_render());
+        let text = unsafe {
+            ViewCell::inspect(&input, |field: &mut InputField| {
+                field.content().text().to_owned()
+            })
+        };
+        assert_eq!(text.unwrap(), "updated");
+        assert_eq!(test.plain(), "updated\n");
+    }
+
+    #[test]
+    fn post_and_wake_from_other_thread() {
+        let mut test = TestScreen::new(Screen::new(Empty), 20, 1);
+        test.render();
+        let handle = test.screen().handle();
+        thread::spawn(move || handle.post(42_i32) && handle.wake())
+            .join()
+            .unwrap();
+        let posts = test.screen_mut().process_messages();
+        let posts: Vec<i32> = posts
+            .into_iter()
+            .map(|post| *post.downcast().unwrap())
+            .collect();
+        assert_eq!(posts, [42]);
+        assert!(test.screen().needs_render());
+    }
+
+    #[test]
+    fn handle_of_dropped_screen_fails() {
+        let handle = Screen::new(Empty).handle();
+        assert!(!handle.post(42_i32));
+        assert!(!handle.wake());
+        assert!(!handle.update_tagged("input", |_| ()));
+    }
+}
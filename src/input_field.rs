@@ -2,7 +2,7 @@
 //! Cursor, selection, etc.
 
 use core::range::Range;
-use std::{error::Error, mem};
+use std::mem;
 
 use copypasta::{ClipboardContext, ClipboardProvider};
 
@@ -111,6 +111,13 @@ impl InputFieldContent {
         &self.text
     }
 
+    /// Byte index of the caret in the text.
+    /// In selection mode, this is the end of the selection that was moved last by
+    /// `select_left` or `select_right`.
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
     pub fn cursor(&self) -> Cursor {
         match (self.caret, self.caret2) {
             (caret, None) => Cursor::Caret(caret),
@@ -284,16 +291,15 @@ impl InputFieldContent {
 
     /// Copy the text to clipboard, if text is selected.
     /// No-op if input field is not in selection mode (returns `Ok`).
-    /// Returns `Err` if error occured during copying using `copypasta`.
-    pub fn copy(
-        &mut self,
-        clipboard: &mut ClipboardContext,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+    /// Returns `Error::Clipboard` if error occured during copying using `copypasta`.
+    pub fn copy(&mut self, clipboard: &mut ClipboardContext) -> crate::Result<()> {
         let Cursor::Selection(selection_range) = self.cursor() else {
             return Ok(());
         };
         let selected_text = self.text[selection_range].to_owned();
-        clipboard.set_contents(selected_text)
+        clipboard
+            .set_contents(selected_text)
+            .map_err(crate::Error::Clipboard)
     }
 
     /// Paste text in clipboard to the input field.
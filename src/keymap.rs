@@ -0,0 +1,482 @@
+//! Key chords, key sequences and keymaps.
+//! Used by `Screen` for keybindings.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display},
+    mem,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A key together with its modifiers, e.g. `<C-s>`.
+///
+/// Parsed from strings like `"C-s"`, `"<C-s>"`, `"F1"`, `"S-Tab"`, `"M-Left"`:
+/// - Modifiers are `C-` (control), `S-` (shift), `A-` or `M-` (alt), in any order.
+/// - Keys are either a single character or one of `Tab`, `BackTab`, `Enter` (or `CR`, `Ret`),
+///   `Esc`, `Space` (or `SPC`), `BS` (or `Backspace`), `Del` (or `Delete`), `Ins` (or `Insert`),
+///   `Home`, `End`, `PgUp` (or `PageUp`), `PgDn` (or `PageDown`), `Up`, `Down`, `Left`,
+///   `Right`, `F1` through `F24`. Key names are case insensitive.
+///
+/// Shift on characters is folded into the character, so `"S-a"` and `"A"` are the same chord, as
+/// are `"S-Tab"` and `"BackTab"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let (code, modifiers) = match code {
+            KeyCode::Char(char) if modifiers.contains(KeyModifiers::SHIFT) => (
+                KeyCode::Char(char.to_ascii_uppercase()),
+                modifiers - KeyModifiers::SHIFT,
+            ),
+            KeyCode::Tab | KeyCode::BackTab if modifiers.contains(KeyModifiers::SHIFT) => {
+                (KeyCode::BackTab, modifiers - KeyModifiers::SHIFT)
+            }
+            code => (code, modifiers),
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn code(self) -> KeyCode {
+        self.code
+    }
+
+    pub fn modifiers(self) -> KeyModifiers {
+        self.modifiers
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key_event: KeyEvent) -> Self {
+        Self::new(key_event.code, key_event.modifiers)
+    }
+}
+
+impl From<KeyCode> for KeyChord {
+    fn from(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+}
+
+/// Error for parsing a `KeyChord` from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyError {
+    input: String,
+}
+
+impl Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid key: {:?}", self.input)
+    }
+}
+
+impl Error for ParseKeyError {}
+
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("Tab", KeyCode::Tab),
+    ("BackTab", KeyCode::BackTab),
+    ("Enter", KeyCode::Enter),
+    ("CR", KeyCode::Enter),
+    ("Ret", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Space", KeyCode::Char(' ')),
+    ("SPC", KeyCode::Char(' ')),
+    ("BS", KeyCode::Backspace),
+    ("Backspace", KeyCode::Backspace),
+    ("Del", KeyCode::Delete),
+    ("Delete", KeyCode::Delete),
+    ("Ins", KeyCode::Insert),
+    ("Insert", KeyCode::Insert),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PgUp", KeyCode::PageUp),
+    ("PageUp", KeyCode::PageUp),
+    ("PgDn", KeyCode::PageDown),
+    ("PageDown", KeyCode::PageDown),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+];
+
+fn parse_key_code(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(char), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(char));
+    }
+    if let Some((_, code)) = NAMED_KEYS
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+    {
+        return Some(*code);
+    }
+    let n = name.strip_prefix(['F', 'f'])?.parse::<u8>().ok()?;
+    (1..=24).contains(&n).then_some(KeyCode::F(n))
+}
+
+impl FromStr for KeyChord {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseKeyError {
+            input: s.to_owned(),
+        };
+        let mut rest = s
+            .strip_prefix('<')
+            .and_then(|s| s.strip_suffix('>'))
+            .unwrap_or(s);
+        let mut modifiers = KeyModifiers::NONE;
+        while let Some((prefix, tail)) = rest.split_once('-') {
+            if tail.is_empty() {
+                // `C--` is control and minus.
+                break;
+            }
+            modifiers |= match prefix {
+                "C" => KeyModifiers::CONTROL,
+                "S" => KeyModifiers::SHIFT,
+                "A" | "M" => KeyModifiers::ALT,
+                _ => return Err(error()),
+            };
+            rest = tail;
+        }
+        let code = parse_key_code(rest).ok_or_else(error)?;
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "C-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "M-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "S-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(char) => write!(f, "{char}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => match NAMED_KEYS.iter().find(|(_, named)| *named == code) {
+                Some((name, _)) => write!(f, "{name}"),
+                None => write!(f, "{code:?}"),
+            },
+        }
+    }
+}
+
+/// The result of feeding a key chord into a `Keymap`, see `Keymap::feed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyMatch<C> {
+    /// The key chord completed a bound key sequence.
+    Matched(C),
+    /// The key chord is part of a bound key sequence that is not complete yet.
+    Pending,
+    /// The key chord is not part of any bound key sequence.
+    Unmatched,
+    /// The key chord does not continue the pending keys into any bound key sequence.
+    Interrupted(Interrupted<C>),
+}
+
+/// Pending keys of a `Keymap` that did not complete a bound key sequence, either because a key
+/// chord did not continue them (see `Keymap::feed`) or because they timed out (see
+/// `Keymap::take_timed_out`).
+///
+/// If the keys start with a bound key sequence, its command should be performed, otherwise the
+/// first key should be handled as if it was not bound. Either way, the remaining keys (see
+/// `Interrupted::rest`) should then be fed into the keymap again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interrupted<C> {
+    /// The pending keys, followed by the key chord that did not continue them, if any.
+    pub keys: Vec<KeyChord>,
+    /// The command of the longest bound key sequence the keys start with, and its length.
+    pub prefix: Option<(C, usize)>,
+}
+
+impl<C> Interrupted<C> {
+    /// Number of leading keys handled by `prefix`, or `1` for the first key if there is no prefix.
+    pub fn consumed(&self) -> usize {
+        self.prefix.as_ref().map_or(1, |&(_, len)| len)
+    }
+
+    /// The keys after the consumed ones (see `Interrupted::consumed`).
+    pub fn rest(&self) -> &[KeyChord] {
+        self.keys.get(self.consumed()..).unwrap_or_default()
+    }
+}
+
+/// Maps key sequences (e.g. `"C-x C-s"`, `"g g"`, `"<leader> f"`) to commands of type `C`.
+///
+/// Key sequences are written as key chords (see `KeyChord`) separated by whitespace. The token
+/// `<leader>` stands for the leader key, which must be set (see `Keymap::set_leader`) before
+/// binding key sequences using it.
+///
+/// While a key sequence is being typed, the keymap keeps the keys typed so far as pending keys. If
+/// the next key does not continue them into a bound key sequence, or no key is typed for the
+/// duration of the timeout, the pending keys are given back as `Interrupted`, together with the
+/// command of the longest bound key sequence they start with (e.g. `"g"` while `"g g"` is also
+/// bound).
+#[derive(Debug, Clone)]
+pub struct Keymap<C> {
+    bindings: HashMap<Vec<KeyChord>, C>,
+    leader: Option<KeyChord>,
+    pending: Vec<KeyChord>,
+    last_key_time: Option<Instant>,
+    timeout: Duration,
+}
+
+impl<C> Default for Keymap<C> {
+    fn default() -> Self {
+        Self {
+            bindings: HashMap::new(),
+            leader: None,
+            pending: Vec::new(),
+            last_key_time: None,
+            timeout: Duration::from_secs(1),
+        }
+    }
+}
+
+impl<C> Keymap<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the key chord `<leader>` stands for in key sequences.
+    pub fn set_leader(&mut self, leader: KeyChord) {
+        self.leader = Some(leader);
+    }
+
+    /// Set how long to wait for the next key in a key sequence.
+    /// Defaults to one second.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Parse a key sequence.
+    pub fn parse_keys(&self, keys: &str) -> Result<Vec<KeyChord>, ParseKeyError> {
+        let sequence = keys
+            .split_whitespace()
+            .map(
+                |chord| match (chord.eq_ignore_ascii_case("<leader>"), self.leader) {
+                    (true, Some(leader)) => Ok(leader),
+                    (true, None) => Err(ParseKeyError {
+                        input: chord.to_owned(),
+                    }),
+                    (false, _) => chord.parse(),
+                },
+            )
+            .collect::<Result<Vec<KeyChord>, ParseKeyError>>()?;
+        if sequence.is_empty() {
+            return Err(ParseKeyError {
+                input: keys.to_owned(),
+            });
+        }
+        Ok(sequence)
+    }
+
+    /// Bind a key sequence to a command, parsed from a string.
+    /// Replaces the previous binding of the same key sequence, if any.
+    pub fn bind(&mut self, keys: &str, command: C) -> Result<(), ParseKeyError> {
+        self.bindings.insert(self.parse_keys(keys)?, command);
+        Ok(())
+    }
+
+    /// Remove the binding of a key sequence, parsed from a string.
+    /// Returns the command it was bound to, if any.
+    pub fn unbind(&mut self, keys: &str) -> Result<Option<C>, ParseKeyError> {
+        Ok(self.bindings.remove(&self.parse_keys(keys)?))
+    }
+
+    /// The command a key sequence is bound to.
+    pub fn get(&self, sequence: &[KeyChord]) -> Option<&C> {
+        self.bindings.get(sequence)
+    }
+
+    /// Keys typed so far of a key sequence that is not complete yet.
+    pub fn pending(&self) -> &[KeyChord] {
+        &self.pending
+    }
+
+    /// Discard the pending keys.
+    pub fn clear_pending(&mut self) {
+        self.pending.clear();
+        self.last_key_time = None;
+    }
+
+    /// When the pending keys time out, if there are any.
+    pub fn deadline(&self) -> Option<Instant> {
+        Some(self.last_key_time? + self.timeout)
+    }
+
+    /// If there is a bound key sequence longer than the pending keys that starts with them.
+    fn pending_is_prefix(&self) -> bool {
+        self.bindings.keys().any(|sequence| {
+            sequence.len() > self.pending.len() && sequence.starts_with(&self.pending)
+        })
+    }
+}
+
+impl<C: Clone> Keymap<C> {
+    /// Feed a key chord typed at `now`.
+    ///
+    /// If the key chord does not continue the pending keys into any bound key sequence, the
+    /// pending keys and the key chord are returned as `KeyMatch::Interrupted`.
+    pub fn feed(&mut self, chord: KeyChord, now: Instant) -> KeyMatch<C> {
+        self.pending.push(chord);
+        if self.pending_is_prefix() {
+            self.last_key_time = Some(now);
+            return KeyMatch::Pending;
+        }
+        let keys = mem::take(&mut self.pending);
+        self.last_key_time = None;
+        if let Some(command) = self.bindings.get(&keys) {
+            return KeyMatch::Matched(command.clone());
+        }
+        match keys.len() {
+            1 => KeyMatch::Unmatched,
+            _ => KeyMatch::Interrupted(self.interrupt(keys)),
+        }
+    }
+
+    /// If the pending keys have timed out at `now`, take them.
+    pub fn take_timed_out(&mut self, now: Instant) -> Option<Interrupted<C>> {
+        if self.deadline()? > now {
+            return None;
+        }
+        let keys = mem::take(&mut self.pending);
+        self.last_key_time = None;
+        Some(self.interrupt(keys))
+    }
+
+    /// Find the longest bound key sequence `keys` start with.
+    fn interrupt(&self, keys: Vec<KeyChord>) -> Interrupted<C> {
+        let prefix = (1..=keys.len())
+            .rev()
+            .find_map(|len| Some((self.bindings.get(&keys[..len])?.clone(), len)));
+        Interrupted { keys, prefix }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(keys: &str) -> Vec<KeyChord> {
+        Keymap::<()>::new().parse_keys(keys).unwrap()
+    }
+
+    fn chord(chord: &str) -> KeyChord {
+        chord.parse().unwrap()
+    }
+
+    fn keymap(bindings: &[&'static str]) -> Keymap<&'static str> {
+        let mut keymap = Keymap::new();
+        for keys in bindings {
+            keymap.bind(keys, *keys).unwrap();
+        }
+        keymap
+    }
+
+    #[test]
+    fn feed_matches_sequence() {
+        let mut keymap = keymap(&["C-x C-s", "C-q"]);
+        let now = Instant::now();
+        assert_eq!(keymap.feed(chord("C-q"), now), KeyMatch::Matched("C-q"));
+        assert_eq!(keymap.feed(chord("C-x"), now), KeyMatch::Pending);
+        assert_eq!(keymap.pending(), keys("C-x"));
+        assert_eq!(keymap.deadline(), Some(now + Duration::from_secs(1)));
+        assert_eq!(keymap.feed(chord("C-s"), now), KeyMatch::Matched("C-x C-s"));
+        assert_eq!(keymap.pending(), []);
+        assert_eq!(keymap.deadline(), None);
+        assert_eq!(keymap.feed(chord("a"), now), KeyMatch::Unmatched);
+    }
+
+    #[test]
+    fn feed_gives_back_interrupted_keys() {
+        let mut keymap = keymap(&["g g"]);
+        let now = Instant::now();
+        assert_eq!(keymap.feed(chord("g"), now), KeyMatch::Pending);
+        let KeyMatch::Interrupted(interrupted) = keymap.feed(chord("x"), now) else {
+            panic!("expected interrupted keys");
+        };
+        assert_eq!(interrupted.keys, keys("g x"));
+        assert_eq!(interrupted.prefix, None);
+        assert_eq!(interrupted.consumed(), 1);
+        assert_eq!(interrupted.rest(), keys("x"));
+        assert_eq!(keymap.pending(), []);
+    }
+
+    #[test]
+    fn feed_finds_bound_prefix() {
+        let mut keymap = keymap(&["g", "g g", "a b", "a b c d"]);
+        let now = Instant::now();
+        assert_eq!(keymap.feed(chord("g"), now), KeyMatch::Pending);
+        let KeyMatch::Interrupted(interrupted) = keymap.feed(chord("x"), now) else {
+            panic!("expected interrupted keys");
+        };
+        assert_eq!(interrupted.prefix, Some(("g", 1)));
+        assert_eq!(interrupted.rest(), keys("x"));
+
+        assert_eq!(keymap.feed(chord("a"), now), KeyMatch::Pending);
+        assert_eq!(keymap.feed(chord("b"), now), KeyMatch::Pending);
+        assert_eq!(keymap.feed(chord("c"), now), KeyMatch::Pending);
+        let KeyMatch::Interrupted(interrupted) = keymap.feed(chord("x"), now) else {
+            panic!("expected interrupted keys");
+        };
+        assert_eq!(interrupted.keys, keys("a b c x"));
+        assert_eq!(interrupted.prefix, Some(("a b", 2)));
+        assert_eq!(interrupted.rest(), keys("c x"));
+    }
+
+    #[test]
+    fn feed_with_leader() {
+        let mut keymap = Keymap::new();
+        keymap.set_leader(chord("Space"));
+        keymap.bind("<leader> f", "find").unwrap();
+        let now = Instant::now();
+        assert_eq!(keymap.feed(chord("a"), now), KeyMatch::Unmatched);
+        assert_eq!(keymap.feed(chord("Space"), now), KeyMatch::Pending);
+        let KeyMatch::Interrupted(interrupted) = keymap.feed(chord("b"), now) else {
+            panic!("expected interrupted keys");
+        };
+        assert_eq!(interrupted.keys, keys("Space b"));
+        assert_eq!(interrupted.prefix, None);
+        assert_eq!(keymap.feed(chord("Space"), now), KeyMatch::Pending);
+        assert_eq!(keymap.feed(chord("f"), now), KeyMatch::Matched("find"));
+    }
+
+    #[test]
+    fn take_timed_out() {
+        let mut keymap = keymap(&["g", "g g", "C-x C-s"]);
+        let now = Instant::now();
+        let timeout = Duration::from_millis(500);
+        keymap.set_timeout(timeout);
+        assert_eq!(keymap.take_timed_out(now), None);
+
+        assert_eq!(keymap.feed(chord("g"), now), KeyMatch::Pending);
+        assert_eq!(keymap.take_timed_out(now + timeout / 2), None);
+        assert_eq!(keymap.pending(), keys("g"));
+        let interrupted = keymap.take_timed_out(now + timeout).unwrap();
+        assert_eq!(interrupted.keys, keys("g"));
+        assert_eq!(interrupted.prefix, Some(("g", 1)));
+        assert_eq!(interrupted.rest(), []);
+        assert_eq!(keymap.pending(), []);
+        assert_eq!(keymap.take_timed_out(now + timeout), None);
+
+        assert_eq!(keymap.feed(chord("C-x"), now), KeyMatch::Pending);
+        let interrupted = keymap.take_timed_out(now + timeout).unwrap();
+        assert_eq!(interrupted.keys, keys("C-x"));
+        assert_eq!(interrupted.prefix, None);
+        assert_eq!(interrupted.rest(), []);
+    }
+}
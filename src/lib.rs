@@ -1,61 +1,53 @@
-#![feature(never_type, new_range_api)]
-
+#![feature(never_type)]
+
+mod ansi;
+pub mod asciicast;
+pub mod editor;
+mod error;
+pub mod event_loop;
+pub mod export;
+pub mod handle;
 pub mod input_field;
+pub mod keymap;
+#[cfg(feature = "record")]
+pub mod record;
+pub mod terminal;
+pub mod testing;
+pub mod theme;
 pub mod view_tuple;
 pub mod views;
 
-use std::io::{self, stdout, Stdout};
-
-use ratatui::{
-    backend::{Backend, CrosstermBackend},
-    crossterm::{self, event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers}}, Terminal,
-};
+use ratatui::{backend::Backend, Terminal};
 
+pub use error::{Error, Result};
+use event_loop::EventLoop;
+use terminal::{CursorStyleBackend, TerminalBuilder, TerminalGuard};
 use views::{Screen, View};
 
-pub fn setup_terminal() -> Terminal<CrosstermBackend<Stdout>> {
-    use crossterm::event::EnableMouseCapture;
-    crossterm::execute!(stdout(), EnableMouseCapture).unwrap();
-    ratatui::init()
+/// Set up a fullscreen terminal, see `TerminalBuilder` for other setups.
+/// The terminal is restored when the returned guard is dropped, or by `restore_terminal`.
+pub fn setup_terminal() -> Result<TerminalGuard> {
+    TerminalBuilder::new().build_guard()
 }
 
-pub fn restore_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) {
-    use crossterm::event::DisableMouseCapture;
-    terminal.show_cursor().unwrap();
-    crossterm::execute!(stdout(), DisableMouseCapture).unwrap();
-    ratatui::restore()
+/// Restore the terminal set up by `setup_terminal`.
+pub fn restore_terminal(terminal: TerminalGuard) -> Result<()> {
+    terminal.restore()
 }
 
 /// Shorthand for rendering a view with no dynamic parts.
 /// For rendering views with dynamic parts, use `Screen`.
-pub fn render<V: View, B: Backend>(terminal: &mut Terminal<B>, view: V) -> io::Result<()> {
+pub fn render<V: View, B: Backend>(terminal: &mut Terminal<B>, view: V) -> Result<()> {
     let screen = Screen::new(view);
     screen.render(terminal)
 }
 
-/// Simple event loop for just rendering a `Screen` with nothing else, ends on `<C-q>`.
-pub fn default_event_loop<V: View, B: Backend>(
+/// Simple event loop for just rendering a `Screen` with nothing else, ends on `<C-q>` if no view
+/// handled it.
+/// For configuring the event loop, use `EventLoop`.
+pub fn default_event_loop<V: View, B: CursorStyleBackend>(
     terminal: &mut Terminal<B>,
     screen: &mut Screen<V>,
-) -> io::Result<()> {
-    'event_loop: loop {
-        screen.render(terminal).unwrap();
-        if !event::poll(std::time::Duration::from_millis(100)).unwrap() {
-            continue 'event_loop;
-        }
-        match event::read().unwrap() {
-            Event::Key(KeyEvent {
-                code: KeyCode::Char('q'),
-                modifiers: KeyModifiers::CONTROL,
-                kind: KeyEventKind::Press,
-                state: _,
-            }) => {
-                break 'event_loop Ok(());
-            }
-            event => {
-                screen.handle_event(event);
-                continue 'event_loop;
-            }
-        }
-    }
+) -> Result<()> {
+    EventLoop::new().run(terminal, screen)
 }
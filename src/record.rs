@@ -0,0 +1,324 @@
+//! Recording of terminal events into files, and replaying them into a `Screen`, for reproducing
+//! sessions.
+//!
+//! Recordings are line-delimited JSON, one `Entry` per line, e.g.
+//! `{"time":0,"size":{"width":80,"height":24}}` followed by
+//! `{"time":1520,"event":{"Key":{"code":{"Char":"a"},...}}}`.
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::{backend::Backend, crossterm::event::Event, Terminal};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    testing::TestScreen,
+    views::{EventResult, Screen, View},
+};
+
+/// Size of the terminal when replaying a recording that does not contain one.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// One line of a recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Milliseconds since the recording started.
+    pub time: u64,
+    #[serde(flatten)]
+    pub kind: EntryKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Size of the terminal when the recording started.
+    /// Later size changes are recorded as `Event::Resize`.
+    Size { width: u16, height: u16 },
+    /// A terminal event passed into the screen.
+    Event(Event),
+}
+
+/// Writes entries of a recording, see the module documentation for the format.
+/// Each entry is flushed immediately, so recordings survive crashes.
+/// `EventLoop::record` sets up a recorder for the event loop.
+#[derive(Debug)]
+pub struct Recorder<W: Write> {
+    writer: W,
+    start: Instant,
+}
+
+impl Recorder<BufWriter<File>> {
+    /// Record into a file, replacing it if it exists.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Record into a writer, the recording starts now.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            start: Instant::now(),
+        }
+    }
+
+    fn record(&mut self, kind: EntryKind) -> io::Result<()> {
+        let entry = Entry {
+            time: self
+                .start
+                .elapsed()
+                .as_millis()
+                .try_into()
+                .unwrap_or(u64::MAX),
+            kind,
+        };
+        serde_json::to_writer(&mut self.writer, &entry)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    /// Record the size of the terminal.
+    pub fn record_size(&mut self, width: u16, height: u16) -> io::Result<()> {
+        self.record(EntryKind::Size { width, height })
+    }
+
+    pub fn record_event(&mut self, event: &Event) -> io::Result<()> {
+        self.record(EntryKind::Event(event.clone()))
+    }
+
+    /// Record an event, then pass it into the screen.
+    pub fn handle_event<V: View>(
+        &mut self,
+        screen: &mut Screen<V>,
+        event: Event,
+    ) -> io::Result<EventResult> {
+        self.record_event(&event)?;
+        Ok(screen.handle_event(event))
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Replays a recording into a `Screen`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Replayer {
+    entries: Vec<Entry>,
+}
+
+impl Replayer {
+    pub fn new(entries: Vec<Entry>) -> Self {
+        Self { entries }
+    }
+
+    /// Read a recording from a file.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a recording, one entry per line. Empty lines are skipped.
+    pub fn read(reader: impl BufRead) -> io::Result<Self> {
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line)?);
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Size of the terminal when the recording started, if it was recorded.
+    pub fn size(&self) -> Option<(u16, u16)> {
+        self.entries.iter().find_map(|entry| match entry.kind {
+            EntryKind::Size { width, height } => Some((width, height)),
+            EntryKind::Event(_) => None,
+        })
+    }
+
+    /// Pass the recorded events into a screen as fast as possible, rendering it on the terminal
+    /// whenever it changed.
+    /// Key timeouts and timers of the screen follow the recorded time, not the real time.
+    /// Recorded size changes are passed into the screen as `Event::Resize`, the terminal itself is
+    /// not resized.
+    pub fn replay<V: View, B: Backend>(
+        &self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<V>,
+    ) -> crate::Result<()> {
+        self.replay_impl(terminal, screen, false)
+    }
+
+    /// Like `Replayer::replay`, but keeps the recorded time between events.
+    pub fn replay_in_real_time<V: View, B: Backend>(
+        &self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<V>,
+    ) -> crate::Result<()> {
+        self.replay_impl(terminal, screen, true)
+    }
+
+    fn replay_impl<V: View, B: Backend>(
+        &self,
+        terminal: &mut Terminal<B>,
+        screen: &mut Screen<V>,
+        real_time: bool,
+    ) -> crate::Result<()> {
+        let start = Instant::now();
+        start_replay(screen, start);
+        let result = self.entries.iter().try_for_each(|entry| {
+            if screen.needs_render() {
+                screen.render(terminal)?;
+            }
+            let due = start + Duration::from_millis(entry.time);
+            if real_time {
+                thread::sleep(due.saturating_duration_since(Instant::now()));
+            }
+            advance_to(screen, due);
+            match &entry.kind {
+                &EntryKind::Size { width, height } => {
+                    screen.handle_event(Event::Resize(width, height));
+                }
+                EntryKind::Event(event) => {
+                    screen.handle_event(event.clone());
+                }
+            }
+            screen.process_messages();
+            Ok::<_, crate::Error>(())
+        });
+        screen.set_virtual_now(None);
+        result?;
+        if screen.needs_render() {
+            screen.render(terminal)?;
+        }
+        Ok(())
+    }
+
+    /// Replay the recording into a screen without a terminal, as fast as possible.
+    /// The returned `TestScreen` has the recorded size and is resized with the recording, so the
+    /// outcome can be inspected and compared against snapshots.
+    /// Like with `Replayer::replay`, key timeouts and timers follow the recorded time.
+    pub fn replay_headless<'a, V: View + 'a>(&self, screen: Screen<'a, V>) -> TestScreen<'a, V> {
+        let (width, height) = self.size().unwrap_or(DEFAULT_SIZE);
+        let mut test_screen = TestScreen::new(screen, width, height);
+        let start = Instant::now();
+        start_replay(test_screen.screen_mut(), start);
+        for entry in &self.entries {
+            advance_to(
+                test_screen.screen_mut(),
+                start + Duration::from_millis(entry.time),
+            );
+            match &entry.kind {
+                &EntryKind::Size { width, height }
+                | &EntryKind::Event(Event::Resize(width, height)) => {
+                    test_screen.resize(width, height);
+                }
+                EntryKind::Event(event) => {
+                    test_screen.send(event.clone());
+                }
+            }
+        }
+        test_screen.screen_mut().set_virtual_now(None);
+        test_screen.render();
+        test_screen
+    }
+}
+
+/// Make `start` the current time of a screen about to replay a recording, restarting its timers
+/// then, so they fire at the same recorded times however long ago the screen was built.
+fn start_replay<V: View>(screen: &mut Screen<V>, start: Instant) {
+    screen.set_virtual_now(Some(start));
+    screen.restart_timers();
+}
+
+/// Make `due` the current time of a screen replaying a recording, handling key timeouts and
+/// timers that are due by then, as if the recorded time had passed.
+fn advance_to<V: View>(screen: &mut Screen<V>, due: Instant) {
+    screen.set_virtual_now(Some(due));
+    screen.handle_key_timeout();
+    screen.handle_timers();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{
+        testing::{fixtures::Ticker, key_events},
+        views::{InputField, ScreenBuilder, Timer, ViewCell},
+    };
+
+    fn key_entries(keys: &str, times: &[u64]) -> Vec<Entry> {
+        key_events(keys)
+            .unwrap()
+            .into_iter()
+            .zip(times)
+            .map(|(event, &time)| Entry {
+                time,
+                kind: EntryKind::Event(event),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replay_follows_recorded_key_timeouts() {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(InputField::default());
+        builder.initial_focus(&input);
+        let mut screen = builder.finish(input.clone());
+        screen.bind("g g", |_| ()).unwrap();
+        screen.set_key_timeout(Duration::from_millis(500));
+        let replayer = Replayer::new(key_entries("g g x", &[0, 1000, 1100]));
+        let test_screen = replayer.replay_headless(screen);
+        assert_eq!(test_screen.screen().pending_keys(), []);
+        let text = unsafe {
+            ViewCell::inspect(&input, |field: &mut InputField| {
+                field.content().text().to_owned()
+            })
+        };
+        assert_eq!(text.unwrap(), "ggx");
+    }
+
+    #[test]
+    fn replay_follows_recorded_timers() {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let mut builder = ScreenBuilder::new();
+        let ticker = builder
+            .view_cell(Ticker(ticks.clone()))
+            .timer(Timer::Interval(Duration::from_secs(1)));
+        let screen = builder.finish(ticker);
+        let replayer = Replayer::new(key_entries("a b c", &[0, 1500, 2500]));
+        replayer.replay_headless(screen);
+        assert_eq!(ticks.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn replay_starts_timers_with_the_recording() {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let mut builder = ScreenBuilder::new();
+        let ticker = builder
+            .view_cell(Ticker(ticks.clone()))
+            .timer(Timer::Interval(Duration::from_millis(100)));
+        let screen = builder.finish(ticker);
+        thread::sleep(Duration::from_millis(60));
+        let replayer = Replayer::new(key_entries("a b", &[0, 50]));
+        replayer.replay_headless(screen);
+        assert_eq!(ticks.load(Ordering::Relaxed), 0);
+    }
+}
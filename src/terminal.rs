@@ -0,0 +1,464 @@
+//! Setting up, restoring and suspending the terminal.
+//!
+//! `TerminalBuilder` sets up the terminal with a fullscreen, inline or fixed viewport. The setup
+//! is remembered, so `restore` and `suspend` undo exactly what was set up.
+//! The terminal is also restored on panics, and when a `TerminalGuard` is dropped.
+//!
+//! While the terminal is set up, `SIGTSTP` sent from outside (e.g. by `kill -TSTP`) suspends the
+//! running `EventLoop` like its suspend key, and the terminal is set up again on `SIGCONT`, e.g.
+//! after the process was stopped by `SIGSTOP`.
+
+#[cfg(unix)]
+use std::thread;
+use std::{
+    io::{self, stdout, Stdout, Write},
+    ops::{Deref, DerefMut},
+    panic,
+    sync::{Mutex, Once, PoisonError},
+};
+
+use ratatui::{
+    backend::{Backend, CrosstermBackend, TestBackend},
+    crossterm::{
+        cursor::{SetCursorStyle, Show},
+        event::{
+            DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        },
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    layout::{Position, Rect},
+    Terminal, TerminalOptions, Viewport,
+};
+
+#[cfg(unix)]
+use signal_hook::{
+    consts::{SIGCONT, SIGSTOP, SIGTSTP},
+    iterator::Signals,
+    low_level,
+};
+
+#[cfg(unix)]
+use crate::handle::{ScreenHandle, ScreenMessage};
+use crate::{views::CursorStyle, Error, Result};
+
+/// The setup of the terminal, while it is set up.
+static ACTIVE_SETUP: Mutex<Option<TerminalBuilder>> = Mutex::new(None);
+
+/// The running event loop that `SIGTSTP` and `SIGCONT` are forwarded to, see `forward_signals`.
+#[cfg(unix)]
+static SIGNAL_RECEIVER: Mutex<Option<ScreenHandle>> = Mutex::new(None);
+
+/// Sets up the terminal for running a `Screen`, in builder style, e.g.
+/// `TerminalBuilder::new().inline(8).build()`.
+///
+/// The terminal is put into raw mode, with mouse capture and bracketed paste enabled by default.
+/// A fullscreen viewport also switches to the alternate screen, inline and fixed viewports render
+/// into the normal screen, e.g. below the shell prompt.
+/// A panic hook is installed that restores the terminal before the panic message is printed, and
+/// on Unix, `SIGTSTP` and `SIGCONT` are handled (see the module documentation).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalBuilder {
+    viewport: Viewport,
+    mouse_capture: bool,
+    bracketed_paste: bool,
+}
+
+impl Default for TerminalBuilder {
+    fn default() -> Self {
+        Self {
+            viewport: Viewport::Fullscreen,
+            mouse_capture: true,
+            bracketed_paste: true,
+        }
+    }
+}
+
+impl TerminalBuilder {
+    /// A fullscreen terminal with mouse capture and bracketed paste.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defaults to `Viewport::Fullscreen`.
+    pub fn viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// Render `height` lines below the cursor, see `Viewport::Inline`.
+    pub fn inline(self, height: u16) -> Self {
+        self.viewport(Viewport::Inline(height))
+    }
+
+    /// Render into a fixed area of the terminal, see `Viewport::Fixed`.
+    pub fn fixed(self, area: Rect) -> Self {
+        self.viewport(Viewport::Fixed(area))
+    }
+
+    /// Whether mouse events are captured. Defaults to `true`.
+    pub fn mouse_capture(mut self, mouse_capture: bool) -> Self {
+        self.mouse_capture = mouse_capture;
+        self
+    }
+
+    /// Whether pastes are received as `Event::Paste` instead of key events. Defaults to `true`.
+    pub fn bracketed_paste(mut self, bracketed_paste: bool) -> Self {
+        self.bracketed_paste = bracketed_paste;
+        self
+    }
+
+    fn uses_alternate_screen(&self) -> bool {
+        self.viewport == Viewport::Fullscreen
+    }
+
+    fn options(&self) -> TerminalOptions {
+        TerminalOptions {
+            viewport: self.viewport.clone(),
+        }
+    }
+
+    /// Set up the terminal. Undo with `restore`.
+    pub fn build(self) -> Result<Terminal<CrosstermBackend<Stdout>>> {
+        set_panic_hook();
+        #[cfg(unix)]
+        handle_signals()?;
+        self.enter()?;
+        match Terminal::with_options(CrosstermBackend::new(stdout()), self.options()) {
+            Ok(terminal) => {
+                *ACTIVE_SETUP.lock()? = Some(self);
+                Ok(terminal)
+            }
+            Err(error) => {
+                let _ = self.leave();
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Like `TerminalBuilder::build`, but restores the terminal when the returned guard is
+    /// dropped, e.g. when returning early with an error.
+    pub fn build_guard(self) -> Result<TerminalGuard> {
+        Ok(TerminalGuard {
+            terminal: self.build()?,
+        })
+    }
+
+    /// Put the terminal into the state of this setup.
+    fn enter(&self) -> Result<()> {
+        enable_raw_mode()?;
+        let mut stdout = stdout();
+        if self.uses_alternate_screen() {
+            execute!(stdout, EnterAlternateScreen)?;
+        }
+        if self.mouse_capture {
+            execute!(stdout, EnableMouseCapture)?;
+        }
+        if self.bracketed_paste {
+            execute!(stdout, EnableBracketedPaste)?;
+        }
+        Ok(())
+    }
+
+    /// Undo `TerminalBuilder::enter`.
+    fn leave(&self) -> Result<()> {
+        let mut stdout = stdout();
+        if self.mouse_capture {
+            execute!(stdout, DisableMouseCapture)?;
+        }
+        if self.bracketed_paste {
+            execute!(stdout, DisableBracketedPaste)?;
+        }
+        execute!(stdout, SetCursorStyle::DefaultUserShape, Show)?;
+        // Raw mode first, it has more side effects than the alternate screen.
+        disable_raw_mode()?;
+        if self.uses_alternate_screen() {
+            execute!(stdout, LeaveAlternateScreen)?;
+        }
+        Ok(())
+    }
+}
+
+/// Restore the terminal before the panic message is printed, installed once.
+fn set_panic_hook() {
+    static SET_PANIC_HOOK: Once = Once::new();
+    SET_PANIC_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if let Ok(mut setup) = ACTIVE_SETUP.lock() {
+                if let Some(setup) = setup.take() {
+                    let _ = setup.leave();
+                }
+            }
+            hook(info);
+        }));
+    });
+}
+
+/// Handle `SIGTSTP` and `SIGCONT` on a thread of their own, installed once.
+/// Both are forwarded to the running event loop, see `forward_signals`. Without one, `SIGTSTP`
+/// restores the terminal and stops the process, like its default action would without raw mode.
+#[cfg(unix)]
+fn handle_signals() -> Result<()> {
+    static HANDLE_SIGNALS: Once = Once::new();
+    let mut result = Ok(());
+    HANDLE_SIGNALS.call_once(|| match Signals::new([SIGTSTP, SIGCONT]) {
+        Ok(mut signals) => {
+            thread::spawn(move || {
+                for signal in signals.forever() {
+                    let message = match signal {
+                        SIGTSTP => ScreenMessage::Suspend,
+                        _ => ScreenMessage::Continued,
+                    };
+                    let receiver = SIGNAL_RECEIVER
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .clone();
+                    let is_forwarded = receiver.is_some_and(|receiver| receiver.send(message));
+                    if signal == SIGTSTP && !is_forwarded {
+                        stop_restored();
+                    }
+                }
+            });
+        }
+        Err(error) => result = Err(error.into()),
+    });
+    result
+}
+
+/// Restore the terminal while the process is stopped, without a terminal to redraw afterwards.
+#[cfg(unix)]
+fn stop_restored() {
+    let setup = ACTIVE_SETUP
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Some(setup) = &setup {
+        let _ = setup.leave();
+    }
+    let _ = low_level::raise(SIGSTOP);
+    if let Some(setup) = &setup {
+        let _ = setup.enter();
+    }
+}
+
+/// Forwards `SIGTSTP` and `SIGCONT` to an event loop until dropped, see `forward_signals`.
+#[cfg(unix)]
+pub(crate) struct SignalForwarding {
+    previous: Option<ScreenHandle>,
+}
+
+/// Forward `SIGTSTP` and `SIGCONT` to the event loop of `handle`, as `ScreenMessage::Suspend` and
+/// `ScreenMessage::Continued`, until the returned guard is dropped.
+/// Signals are only handled once a terminal was set up by `TerminalBuilder::build`.
+#[cfg(unix)]
+pub(crate) fn forward_signals(handle: ScreenHandle) -> SignalForwarding {
+    let previous = SIGNAL_RECEIVER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .replace(handle);
+    SignalForwarding { previous }
+}
+
+#[cfg(unix)]
+impl Drop for SignalForwarding {
+    fn drop(&mut self) {
+        *SIGNAL_RECEIVER
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = self.previous.take();
+    }
+}
+
+/// With an inline viewport, move the cursor to the line below the viewport, so following output
+/// does not overwrite it.
+fn move_below_inline_viewport<B: Backend>(
+    terminal: &mut Terminal<B>,
+    setup: &TerminalBuilder,
+) -> Result<()> {
+    if let Viewport::Inline(_) = setup.viewport {
+        let area = terminal.get_frame().area();
+        terminal.set_cursor_position((0, area.bottom().saturating_sub(1)))?;
+        let mut stdout = stdout();
+        stdout.write_all(b"\r\n")?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// Restore the terminal set up by `TerminalBuilder::build`.
+/// With an inline viewport, the last render stays visible above the cursor.
+pub fn restore<B: Backend>(terminal: &mut Terminal<B>) -> Result<()> {
+    let Some(setup) = ACTIVE_SETUP.lock()?.take() else {
+        return Ok(());
+    };
+    move_below_inline_viewport(terminal, &setup)?;
+    setup.leave()
+}
+
+/// Restore the terminal while running `f`, e.g. a program that uses the terminal itself, then set
+/// it up again. The whole terminal is redrawn on the next render.
+/// Fails with `Error::TerminalNotSetUp` without running `f` if the terminal was not set up by
+/// `TerminalBuilder::build`, or was restored already.
+pub fn run_restored<B: Backend, T>(terminal: &mut Terminal<B>, f: impl FnOnce() -> T) -> Result<T> {
+    let Some(setup) = ACTIVE_SETUP.lock()?.clone() else {
+        return Err(Error::TerminalNotSetUp);
+    };
+    move_below_inline_viewport(terminal, &setup)?;
+    setup.leave()?;
+    let output = f();
+    setup.enter()?;
+    if let Viewport::Inline(_) = setup.viewport {
+        // Below what was printed meanwhile.
+        place_inline_viewport_at_cursor(terminal)?;
+    }
+    terminal.clear()?;
+    Ok(output)
+}
+
+/// Move an inline viewport to the cursor of the terminal.
+fn place_inline_viewport_at_cursor<B: Backend>(terminal: &mut Terminal<B>) -> Result<()> {
+    // `resize` keeps the offset of the last known cursor position into the viewport, so move to
+    // its top first.
+    let cursor = terminal.backend_mut().get_cursor_position()?;
+    let top = terminal.get_frame().area().as_position();
+    terminal.set_cursor_position(top)?;
+    terminal.backend_mut().set_cursor_position(cursor)?;
+    let size = terminal.size()?;
+    terminal.resize(Rect::from((Position::ORIGIN, size)))?;
+    Ok(())
+}
+
+/// Set up the terminal again after the process was stopped from outside and continued, e.g. by
+/// `SIGSTOP`, which can not be handled. The whole terminal is redrawn on the next render.
+pub(crate) fn resume<B: Backend>(terminal: &mut Terminal<B>) -> Result<()> {
+    let Some(setup) = ACTIVE_SETUP.lock()?.clone() else {
+        return Ok(());
+    };
+    setup.enter()?;
+    terminal.clear()?;
+    Ok(())
+}
+
+/// Suspend the process like `<C-z>` in a shell: restore the terminal, stop the process, and set up
+/// the terminal again once the shell continues it (e.g. with `fg`).
+/// The whole terminal is redrawn on the next render.
+///
+/// Since raw mode disables `<C-z>` sending `SIGTSTP`, this is called by `EventLoop` on its suspend
+/// key instead, and on `SIGTSTP` sent from outside. Does not stop the process on platforms
+/// without job control.
+pub fn suspend<B: Backend>(terminal: &mut Terminal<B>) -> Result<()> {
+    if ACTIVE_SETUP.lock()?.is_none() {
+        return Ok(());
+    }
+    // With `SIGSTOP`, as `SIGTSTP` is handled while the terminal is set up.
+    #[cfg(unix)]
+    run_restored(terminal, || low_level::raise(SIGSTOP))??;
+    #[cfg(not(unix))]
+    run_restored(terminal, || ())?;
+    Ok(())
+}
+
+/// A `Backend` that can also set the style of the terminal cursor.
+/// `EventLoop` applies the cursor style of the focused view through this, see
+/// `MutView::cursor_style`.
+pub trait CursorStyleBackend: Backend {
+    fn set_cursor_style(&mut self, cursor_style: CursorStyle) -> io::Result<()>;
+}
+
+impl<W: Write> CursorStyleBackend for CrosstermBackend<W> {
+    fn set_cursor_style(&mut self, cursor_style: CursorStyle) -> io::Result<()> {
+        execute!(self, SetCursorStyle::from(cursor_style))
+    }
+}
+
+/// `TestBackend` has no cursor style, setting it does nothing.
+impl CursorStyleBackend for TestBackend {
+    fn set_cursor_style(&mut self, _cursor_style: CursorStyle) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A terminal set up by `TerminalBuilder::build_guard` (or `setup_terminal`), restored when this is
+/// dropped. Dereferences to the `Terminal`.
+#[derive(Debug)]
+pub struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl TerminalGuard {
+    /// Restore the terminal now, unlike dropping this, errors are returned.
+    pub fn restore(mut self) -> Result<()> {
+        restore(&mut self.terminal)
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = Terminal<CrosstermBackend<Stdout>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Already restored by the panic hook when panicking.
+        let _ = restore(&mut self.terminal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_terminal(builder: &TerminalBuilder) -> Terminal<TestBackend> {
+        Terminal::with_options(TestBackend::new(10, 6), builder.options()).unwrap()
+    }
+
+    #[test]
+    fn viewports() {
+        let fullscreen = TerminalBuilder::new();
+        assert!(fullscreen.uses_alternate_screen());
+        let area = test_terminal(&fullscreen).get_frame().area();
+        assert_eq!(area, Rect::new(0, 0, 10, 6));
+
+        let inline = TerminalBuilder::new().inline(2);
+        assert!(!inline.uses_alternate_screen());
+        let area = test_terminal(&inline).get_frame().area();
+        assert_eq!(area, Rect::new(0, 0, 10, 2));
+
+        let fixed = TerminalBuilder::new().fixed(Rect::new(1, 2, 3, 4));
+        assert!(!fixed.uses_alternate_screen());
+        let area = test_terminal(&fixed).get_frame().area();
+        assert_eq!(area, Rect::new(1, 2, 3, 4));
+    }
+
+    #[test]
+    fn run_restored_places_inline_viewport_at_cursor() {
+        let mut terminal = test_terminal(&TerminalBuilder::new().inline(2));
+        terminal.set_cursor_position((3, 1)).unwrap();
+        // Printed below the viewport while restored.
+        terminal.backend_mut().set_cursor_position((0, 3)).unwrap();
+        place_inline_viewport_at_cursor(&mut terminal).unwrap();
+        assert_eq!(terminal.get_frame().area(), Rect::new(0, 3, 10, 2));
+        // Scrolled up at the bottom of the terminal.
+        terminal.backend_mut().set_cursor_position((0, 5)).unwrap();
+        place_inline_viewport_at_cursor(&mut terminal).unwrap();
+        assert_eq!(terminal.get_frame().area(), Rect::new(0, 4, 10, 2));
+    }
+
+    #[test]
+    fn run_restored_fails_without_setup() {
+        let mut terminal = test_terminal(&TerminalBuilder::new());
+        let mut is_run = false;
+        let result = run_restored(&mut terminal, || is_run = true);
+        assert!(matches!(result, Err(Error::TerminalNotSetUp)));
+        assert!(!is_run);
+    }
+}
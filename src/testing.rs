@@ -0,0 +1,698 @@
+//! Helpers for testing screens: rendering a `Screen` into plain-text or styled dumps, comparing
+//! them against golden files, and feeding synthetic events into it.
+//!
+//! Events can also be written as keystroke scripts, see `parse_script`.
+//!
+//! Golden files are compared as is. When the environment variable `DOMTUI_UPDATE_SNAPSHOTS` is
+//! set (to anything but `0`), missing or mismatching golden files are written instead.
+
+use std::{
+    env,
+    error::Error,
+    fmt::{self, Display, Write as _},
+    fs, io,
+    path::Path,
+};
+
+use ratatui::{
+    backend::{Backend, ClearType, TestBackend, WindowSize},
+    buffer::{Buffer, Cell},
+    crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
+    layout::{Position, Size},
+    style::Color,
+    text::Span,
+    Terminal,
+};
+
+use crate::{
+    export::rows,
+    keymap::{KeyChord, ParseKeyError},
+    views::{EventResult, Screen, View},
+};
+
+#[cfg(test)]
+pub(crate) mod fixtures;
+
+/// Name of the environment variable that makes snapshot assertions write golden files.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "DOMTUI_UPDATE_SNAPSHOTS";
+
+/// A `Screen` together with an in-memory terminal of a fixed size, for tests.
+#[derive(Debug)]
+pub struct TestScreen<'a, V: View + 'a> {
+    screen: Screen<'a, V>,
+    terminal: Terminal<CursorTrackingBackend>,
+}
+
+impl<'a, V: View + 'a> TestScreen<'a, V> {
+    pub fn new(screen: Screen<'a, V>, width: u16, height: u16) -> Self {
+        let backend = CursorTrackingBackend {
+            backend: TestBackend::new(width, height),
+            cursor_is_visible: false,
+        };
+        Self {
+            screen,
+            terminal: Terminal::new(backend).unwrap(),
+        }
+    }
+
+    pub fn screen(&self) -> &Screen<'a, V> {
+        &self.screen
+    }
+
+    pub fn screen_mut(&mut self) -> &mut Screen<'a, V> {
+        &mut self.screen
+    }
+
+    pub fn into_screen(self) -> Screen<'a, V> {
+        self.screen
+    }
+
+    /// Resize the terminal, and pass the resize event into the screen.
+    pub fn resize(&mut self, width: u16, height: u16) -> EventResult {
+        self.terminal.backend_mut().backend.resize(width, height);
+        self.send(Event::Resize(width, height))
+    }
+
+    /// Pass an event into the screen, then run the updates queued by `ScreenHandle`s.
+    /// Like `EventLoop`, the screen is rendered before the event if it changed, so mouse events
+    /// reach the views rendered at their position.
+    pub fn send(&mut self, event: Event) -> EventResult {
+        if self.screen.needs_render() {
+            self.render();
+        }
+        let result = self.screen.handle_event(event);
+        self.screen.process_messages();
+        result
+    }
+
+    /// Pass events into the screen one by one.
+    /// Returns whether the last event was handled.
+    pub fn send_all(&mut self, events: impl IntoIterator<Item = Event>) -> EventResult {
+        events
+            .into_iter()
+            .fold(EventResult::Ignored, |_, event| self.send(event))
+    }
+
+    /// Press a key sequence, see `key_events`.
+    /// Returns whether the last key was handled.
+    ///
+    /// # Panics
+    /// Panics if `keys` is not a valid key sequence.
+    pub fn press(&mut self, keys: &str) -> EventResult {
+        let events = key_events(keys).unwrap_or_else(|error| panic!("{error}"));
+        self.send_all(events)
+    }
+
+    /// Run a keystroke script, see `parse_script`.
+    /// Returns whether the last event was handled.
+    ///
+    /// # Panics
+    /// Panics if `script` is not a valid script.
+    pub fn run(&mut self, script: &str) -> EventResult {
+        let events = parse_script(script).unwrap_or_else(|error| panic!("{error}"));
+        self.send_all(events)
+    }
+
+    /// Type text character by character, see `text_events`.
+    pub fn type_text(&mut self, text: &str) -> EventResult {
+        self.send_all(text_events(text))
+    }
+
+    /// Paste text, as with bracketed paste.
+    pub fn paste(&mut self, text: &str) -> EventResult {
+        self.send(Event::Paste(text.to_owned()))
+    }
+
+    /// Click the left mouse button at a position of the screen.
+    pub fn click(&mut self, column: u16, row: u16) -> EventResult {
+        self.send_all(click_events(column, row))
+    }
+
+    /// Render the screen, returns the resulting buffer.
+    pub fn render(&mut self) -> &Buffer {
+        self.screen.render(&mut self.terminal).unwrap();
+        self.buffer()
+    }
+
+    /// The buffer of the last render.
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().backend.buffer()
+    }
+
+    /// Position of the terminal cursor after the last render, `None` if it is hidden.
+    pub fn cursor(&mut self) -> Option<Position> {
+        let backend = self.terminal.backend_mut();
+        match backend.cursor_is_visible {
+            true => backend.get_cursor_position().ok(),
+            false => None,
+        }
+    }
+
+    /// Render the screen into a plain-text dump, see `plain_dump`.
+    pub fn plain(&mut self) -> String {
+        plain_dump(self.render())
+    }
+
+    /// Render the screen into a styled dump, see `styled_dump`.
+    pub fn styled(&mut self) -> String {
+        self.render();
+        let cursor = self.cursor();
+        styled_dump(self.buffer(), cursor)
+    }
+
+    /// Render the screen and compare the plain-text dump against a golden file.
+    ///
+    /// # Panics
+    /// Panics with a diff if the dump does not match, see `assert_snapshot`.
+    #[track_caller]
+    pub fn assert_plain_snapshot(&mut self, path: impl AsRef<Path>) {
+        assert_snapshot(path, &self.plain());
+    }
+
+    /// Render the screen and compare the styled dump against a golden file.
+    ///
+    /// # Panics
+    /// Panics with a diff if the dump does not match, see `assert_snapshot`.
+    #[track_caller]
+    pub fn assert_styled_snapshot(&mut self, path: impl AsRef<Path>) {
+        assert_snapshot(path, &self.styled());
+    }
+}
+
+/// `TestBackend` does not expose whether the cursor is shown, so keep track of it here.
+#[derive(Debug)]
+struct CursorTrackingBackend {
+    backend: TestBackend,
+    cursor_is_visible: bool,
+}
+
+impl Backend for CursorTrackingBackend {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        self.backend.draw(content)
+    }
+
+    fn append_lines(&mut self, n: u16) -> io::Result<()> {
+        self.backend.append_lines(n)
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.cursor_is_visible = false;
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.cursor_is_visible = true;
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> io::Result<Position> {
+        self.backend.get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> io::Result<()> {
+        self.backend.set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.backend.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+        self.backend.clear_region(clear_type)
+    }
+
+    fn size(&self) -> io::Result<Size> {
+        self.backend.size()
+    }
+
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        self.backend.window_size()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.backend.flush()
+    }
+}
+
+/// Key events of a key sequence, written as key chords separated by whitespace (see
+/// `KeyChord`), e.g. `"C-x C-s"`.
+pub fn key_events(keys: &str) -> Result<Vec<Event>, ParseKeyError> {
+    keys.split_whitespace()
+        .map(|chord| Ok(key_event(chord.parse()?)))
+        .collect()
+}
+
+/// The key press event of a key chord.
+pub fn key_event(chord: KeyChord) -> Event {
+    Event::Key(KeyEvent {
+        code: chord.code(),
+        modifiers: chord.modifiers(),
+        kind: KeyEventKind::Press,
+        state: KeyEventState::NONE,
+    })
+}
+
+/// Key events for typing text character by character.
+/// Line breaks are typed as Enter.
+pub fn text_events(text: &str) -> Vec<Event> {
+    text.chars()
+        .map(|char| match char {
+            '\n' => key_event(KeyCode::Enter.into()),
+            char => key_event(KeyCode::Char(char).into()),
+        })
+        .collect()
+}
+
+/// Mouse events for pressing and releasing the left mouse button at a position.
+pub fn click_events(column: u16, row: u16) -> [Event; 2] {
+    let mouse_event = |kind| {
+        Event::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    };
+    [
+        mouse_event(MouseEventKind::Down(MouseButton::Left)),
+        mouse_event(MouseEventKind::Up(MouseButton::Left)),
+    ]
+}
+
+/// Error for parsing a keystroke script, see `parse_script`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseScriptError {
+    /// Byte offset of the invalid part of the script.
+    offset: usize,
+    message: String,
+}
+
+impl ParseScriptError {
+    fn new(offset: usize, message: impl Into<String>) -> Self {
+        Self {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    /// Byte offset of the invalid part of the script.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Display for ParseScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid script at byte {}: {}",
+            self.offset, self.message
+        )
+    }
+}
+
+impl Error for ParseScriptError {}
+
+/// Parse a keystroke script into events, e.g. `"type 'hello' <Tab> <C-a> <S-Right> <BS>"`.
+///
+/// A script is a whitespace-separated list of:
+/// - `<chord>`: a key press, see `KeyChord` for how chords are written, e.g. `<C-a>`, `<S-Tab>`.
+/// - `type 'text'`: type text character by character, see `text_events`.
+/// - `paste 'text'`: paste text, as with bracketed paste.
+/// - `click column,row`: click the left mouse button at a position, e.g. `click 3,1`.
+///
+/// Text is quoted in `'` or `"`, and may contain the escapes `\'`, `\"`, `\\`, `\n` and `\t`.
+pub fn parse_script(script: &str) -> Result<Vec<Event>, ParseScriptError> {
+    let mut parser = ScriptParser { script, offset: 0 };
+    let mut events = Vec::new();
+    while let Some(offset) = parser.skip_whitespace() {
+        if parser.rest().starts_with('<') {
+            events.push(key_event(parser.chord()?));
+            continue;
+        }
+        match parser.word() {
+            "type" => events.extend(text_events(&parser.quoted()?)),
+            "paste" => events.push(Event::Paste(parser.quoted()?)),
+            "click" => {
+                let (column, row) = parser.position()?;
+                events.extend(click_events(column, row));
+            }
+            word => {
+                return Err(ParseScriptError::new(
+                    offset,
+                    format!("unknown command {word:?}"),
+                ))
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// Parse a keystroke script (see `parse_script`) and pass its events into a screen one by one.
+/// Nothing is passed into the screen if the script is invalid.
+/// Returns whether the last event was handled.
+pub fn run_script<V: View>(
+    screen: &mut Screen<V>,
+    script: &str,
+) -> Result<EventResult, ParseScriptError> {
+    Ok(parse_script(script)?
+        .into_iter()
+        .fold(EventResult::Ignored, |_, event| screen.handle_event(event)))
+}
+
+struct ScriptParser<'s> {
+    script: &'s str,
+    /// Byte offset of the rest of the script.
+    offset: usize,
+}
+
+impl<'s> ScriptParser<'s> {
+    fn rest(&self) -> &'s str {
+        &self.script[self.offset..]
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.offset += len;
+    }
+
+    /// Skip whitespace, returns the offset of the next token, `None` if the script ended.
+    fn skip_whitespace(&mut self) -> Option<usize> {
+        let rest = self.rest();
+        self.advance(rest.len() - rest.trim_start().len());
+        (!self.rest().is_empty()).then_some(self.offset)
+    }
+
+    /// The token until the next whitespace.
+    fn word(&mut self) -> &'s str {
+        let rest = self.rest();
+        let word = &rest[..rest.find(char::is_whitespace).unwrap_or(rest.len())];
+        self.advance(word.len());
+        word
+    }
+
+    /// A key chord in angle brackets, e.g. `<C-a>`.
+    /// The chord ends at a `>` followed by whitespace, another chord or the end of the script, so
+    /// `<C->>` is control and `>`.
+    fn chord(&mut self) -> Result<KeyChord, ParseScriptError> {
+        let offset = self.offset;
+        let rest = self.rest();
+        let end = rest
+            .char_indices()
+            .skip(2)
+            .find(|&(i, char)| {
+                char == '>'
+                    && rest[i + 1..]
+                        .chars()
+                        .next()
+                        .is_none_or(|next| next.is_whitespace() || next == '<')
+            })
+            .map(|(i, _)| i + 1)
+            .ok_or_else(|| ParseScriptError::new(offset, "unterminated key chord"))?;
+        self.advance(end);
+        rest[..end]
+            .parse()
+            .map_err(|error: ParseKeyError| ParseScriptError::new(offset, error.to_string()))
+    }
+
+    /// Text quoted in `'` or `"`, after whitespace.
+    fn quoted(&mut self) -> Result<String, ParseScriptError> {
+        let offset = self.skip_whitespace().unwrap_or(self.offset);
+        let mut chars = self.rest().char_indices();
+        let quote = match chars.next() {
+            Some((_, quote @ ('\'' | '"'))) => quote,
+            _ => return Err(ParseScriptError::new(offset, "expected quoted text")),
+        };
+        let mut text = String::new();
+        while let Some((i, char)) = chars.next() {
+            match char {
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => text.push('\n'),
+                    Some((_, 't')) => text.push('\t'),
+                    Some((_, escaped @ ('\'' | '"' | '\\'))) => text.push(escaped),
+                    _ => return Err(ParseScriptError::new(offset + i, "invalid escape")),
+                },
+                char if char == quote => {
+                    self.advance(i + 1);
+                    return Ok(text);
+                }
+                char => text.push(char),
+            }
+        }
+        Err(ParseScriptError::new(offset, "unterminated quoted text"))
+    }
+
+    /// A position written as `column,row`, after whitespace.
+    fn position(&mut self) -> Result<(u16, u16), ParseScriptError> {
+        let offset = self.skip_whitespace().unwrap_or(self.offset);
+        let word = self.word();
+        word.split_once(',')
+            .and_then(|(column, row)| Some((column.parse().ok()?, row.parse().ok()?)))
+            .ok_or_else(|| ParseScriptError::new(offset, format!("invalid position {word:?}")))
+    }
+}
+
+/// The text of a buffer, one line per row, with trailing whitespace of each line trimmed.
+pub fn plain_dump(buffer: &Buffer) -> String {
+    let mut dump = String::new();
+    for row in rows(buffer) {
+        let line: String = row.iter().map(|(_, cell)| cell.symbol()).collect();
+        dump.push_str(line.trim_end());
+        dump.push('\n');
+    }
+    dump
+}
+
+/// The text of a buffer (as in `plain_dump`), followed by the cursor position and the styles of
+/// the buffer.
+/// Styles are listed as runs of cells of the same style, e.g. `1:0..5 fg=White +BOLD` for row 1,
+/// columns 0 to 4. Cells without any style are not listed.
+pub fn styled_dump(buffer: &Buffer, cursor: Option<Position>) -> String {
+    let mut dump = plain_dump(buffer);
+    match cursor {
+        Some(Position { x, y }) => writeln!(dump, "cursor: {x},{y}").unwrap(),
+        None => writeln!(dump, "cursor: hidden").unwrap(),
+    }
+    dump.push_str("styles:\n");
+    for (y, row) in (buffer.area.top()..).zip(rows(buffer)) {
+        // Runs of cells of the same style, as `(start, end, style)`.
+        let mut runs: Vec<(u16, u16, String)> = Vec::new();
+        for (x, cell) in row {
+            let end = x + Span::raw(cell.symbol()).width().max(1) as u16;
+            let style = describe_style(cell);
+            match runs.last_mut() {
+                Some((_, run_end, run_style)) if *run_style == style => *run_end = end,
+                _ => runs.push((x, end, style)),
+            }
+        }
+        for (start, end, style) in runs {
+            if !style.is_empty() {
+                writeln!(dump, "{y}:{start}..{end} {style}").unwrap();
+            }
+        }
+    }
+    dump
+}
+
+/// Describe the style of a cell, e.g. `fg=White bg=Blue +BOLD`.
+/// Empty if the cell has no style.
+fn describe_style(cell: &Cell) -> String {
+    let mut description = Vec::new();
+    if cell.fg != Color::Reset {
+        description.push(format!("fg={}", cell.fg));
+    }
+    if cell.bg != Color::Reset {
+        description.push(format!("bg={}", cell.bg));
+    }
+    if cell.underline_color != Color::Reset {
+        description.push(format!("underline={}", cell.underline_color));
+    }
+    for (name, _) in cell.modifier.iter_names() {
+        description.push(format!("+{name}"));
+    }
+    description.join(" ")
+}
+
+/// Compare `actual` against the content of a golden file.
+/// If `DOMTUI_UPDATE_SNAPSHOTS` is set, a missing or mismatching golden file is written instead.
+///
+/// # Panics
+/// Panics with a line diff if `actual` does not match the golden file, or if the golden file does
+/// not exist.
+#[track_caller]
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+    let update = env::var_os(UPDATE_SNAPSHOTS_VAR).is_some_and(|value| value != "0");
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(error) if error.kind() == io::ErrorKind::NotFound && !update => panic!(
+            "snapshot {} does not exist, run with {UPDATE_SNAPSHOTS_VAR}=1 to create it\n\
+             actual:\n{actual}",
+            path.display()
+        ),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => panic!("failed to read snapshot {}: {error}", path.display()),
+    };
+    if expected == actual {
+        return;
+    }
+    if update {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, actual).unwrap();
+        return;
+    }
+    panic!(
+        "snapshot {} does not match, run with {UPDATE_SNAPSHOTS_VAR}=1 to update it\n\
+         --- expected\n+++ actual\n{}",
+        path.display(),
+        diff(&expected, actual)
+    );
+}
+
+/// A line diff from `expected` to `actual`, lines prefixed with `-` for removed, `+` for added,
+/// and ` ` for unchanged.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    // Lengths of the longest common subsequences of `expected[i..]` and `actual[j..]`.
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = match expected[i] == actual[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            writeln!(diff, " {}", expected[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            writeln!(diff, "-{}", expected[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(diff, "+{}", actual[j]).unwrap();
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{
+        layout::Rect,
+        style::{Modifier, Style},
+    };
+
+    use super::*;
+
+    #[test]
+    fn diff_of_equal_text() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), " a\n b\n");
+        assert_eq!(diff("", ""), "");
+    }
+
+    #[test]
+    fn diff_of_added_and_removed_lines() {
+        assert_eq!(diff("a\nc\n", "a\nb\nc\n"), " a\n+b\n c\n");
+        assert_eq!(diff("a\nb\nc\n", "a\nc\n"), " a\n-b\n c\n");
+        assert_eq!(diff("", "a\n"), "+a\n");
+        assert_eq!(diff("a\n", ""), "-a\n");
+    }
+
+    #[test]
+    fn diff_of_changed_lines() {
+        assert_eq!(diff("a\nb\nc\n", "a\nx\nc\n"), " a\n-b\n+x\n c\n");
+        assert_eq!(diff("a\nb\nc\nd\n", "b\nc\ne\n"), "-a\n b\n c\n-d\n+e\n");
+    }
+
+    #[test]
+    fn styled_dump_lists_runs() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "ab", Style::new().fg(Color::Red));
+        buffer.set_string(2, 0, "文x", Style::new().fg(Color::Red));
+        buffer.set_string(
+            3,
+            1,
+            "cd",
+            Style::new().bg(Color::Blue).add_modifier(Modifier::BOLD),
+        );
+        let dump = styled_dump(&buffer, Some(Position::new(1, 1)));
+        assert_eq!(
+            dump,
+            "ab文x\n   cd\ncursor: 1,1\nstyles:\n0:0..5 fg=Red\n1:3..5 bg=Blue +BOLD\n"
+        );
+        let dump = styled_dump(&buffer, None);
+        assert!(dump.contains("\ncursor: hidden\n"));
+    }
+
+    fn key(chord: &str) -> Event {
+        key_event(chord.parse().unwrap())
+    }
+
+    fn error_offset(script: &str) -> usize {
+        parse_script(script).unwrap_err().offset()
+    }
+
+    #[test]
+    fn script_chords() {
+        assert_eq!(
+            parse_script("<C-a> <S-Tab>\n<Enter><C->> <<>").unwrap(),
+            [key("C-a"), key("S-Tab"), key("Enter"), key("C->"), key("<")]
+        );
+        assert_eq!(parse_script("  ").unwrap(), []);
+    }
+
+    #[test]
+    fn script_quoting_and_escapes() {
+        assert_eq!(
+            parse_script(r#"type 'a b' type "it's""#).unwrap(),
+            text_events("a bit's")
+        );
+        assert_eq!(
+            parse_script(r#"type 'it\'s \"x\" \\ \t\n'"#).unwrap(),
+            text_events("it's \"x\" \\ \t\n")
+        );
+        assert_eq!(
+            parse_script("paste 'two\\nlines'").unwrap(),
+            [Event::Paste("two\nlines".to_owned())]
+        );
+    }
+
+    #[test]
+    fn script_clicks() {
+        assert_eq!(
+            parse_script("click 3,1 <Tab>").unwrap(),
+            [click_events(3, 1).as_slice(), &[key("Tab")]].concat()
+        );
+    }
+
+    #[test]
+    fn script_errors() {
+        assert_eq!(error_offset("<Tab> jump"), 6);
+        assert_eq!(error_offset("<C-a"), 0);
+        assert_eq!(error_offset("<Tab> <Q-a>"), 6);
+        assert_eq!(error_offset("type x"), 5);
+        assert_eq!(error_offset("type"), 4);
+        assert_eq!(error_offset("type 'abc"), 5);
+        assert_eq!(error_offset("type 'a\\q'"), 7);
+        assert_eq!(error_offset("click 3"), 6);
+        assert_eq!(error_offset("click a,1"), 6);
+        assert_eq!(
+            parse_script("<Tab> jump").unwrap_err().to_string(),
+            "invalid script at byte 6: unknown command \"jump\""
+        );
+    }
+}
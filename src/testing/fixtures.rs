@@ -0,0 +1,105 @@
+//! Views shared by the tests of this crate.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use ratatui::{
+    crossterm::event::{KeyEvent, MouseEvent},
+    layout::Rect,
+    Frame,
+};
+
+use crate::{
+    keymap::KeyChord,
+    views::{EventResult, MutView, Timer},
+};
+
+/// What happened to `Probe`s, shared between them.
+pub(crate) type Log = Arc<Mutex<Vec<String>>>;
+
+/// Take the entries of a log written so far.
+pub(crate) fn take(log: &Log) -> Vec<String> {
+    std::mem::take(&mut log.lock().unwrap())
+}
+
+/// A focusable view that writes focus changes, key and mouse events into a `Log`, e.g.
+/// `"a focus"`, `"a key C-x"` or `"a mouse 1,2"`, and handles the events.
+pub(crate) struct Probe {
+    name: &'static str,
+    log: Log,
+    is_focusable: bool,
+    result: EventResult,
+}
+
+impl Probe {
+    pub(crate) fn new(name: &'static str, log: &Log) -> Self {
+        Self {
+            name,
+            log: log.clone(),
+            is_focusable: true,
+            result: EventResult::Handled,
+        }
+    }
+
+    pub(crate) fn unfocusable(mut self) -> Self {
+        self.is_focusable = false;
+        self
+    }
+
+    /// Write events into the log, but ignore them.
+    pub(crate) fn ignoring(mut self) -> Self {
+        self.result = EventResult::Ignored;
+        self
+    }
+
+    fn write(&self, entry: impl AsRef<str>) {
+        let entry = format!("{} {}", self.name, entry.as_ref());
+        self.log.lock().unwrap().push(entry);
+    }
+}
+
+impl MutView for Probe {
+    fn render(&self, _frame: &mut Frame, _area: Rect, _is_focused: bool) {}
+
+    fn is_focusable(&self) -> bool {
+        self.is_focusable
+    }
+
+    fn on_focus(&mut self, _timer: &mut Option<Timer>) {
+        self.write("focus");
+    }
+
+    fn on_unfocus(&mut self, _timer: &mut Option<Timer>) {
+        self.write("unfocus");
+    }
+
+    fn on_key_event(&mut self, key_event: KeyEvent, _timer: &mut Option<Timer>) -> EventResult {
+        self.write(format!("key {}", KeyChord::from(key_event)));
+        self.result
+    }
+
+    fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        _timer: &mut Option<Timer>,
+    ) -> EventResult {
+        self.write(format!("mouse {},{}", mouse_event.column, mouse_event.row));
+        self.result
+    }
+}
+
+/// Counts how often its timer fired.
+pub(crate) struct Ticker(pub(crate) Arc<AtomicUsize>);
+
+impl MutView for Ticker {
+    fn render(&self, _frame: &mut Frame, _area: Rect, _is_focused: bool) {}
+
+    fn on_tick(&mut self, _elapsed: Duration, _timer: &mut Option<Timer>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
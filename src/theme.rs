@@ -0,0 +1,66 @@
+//! Colors of the built-in views, by semantic role.
+
+use ratatui::style::{Color, Modifier, Style};
+
+/// Styles of the built-in views by semantic role, provided by the `Screen` to its views (see
+/// `Screen::set_theme`, `View::set_theme` and `MutView::set_theme`).
+/// Styles set on a view itself are patched over the styles of the theme.
+/// Custom views may use the roles as well, through `Screen::theme`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Theme {
+    /// Regular text, e.g. of input fields.
+    pub text: Style,
+    /// Less important text, e.g. placeholders, and borders of unfocused views.
+    pub muted: Style,
+    /// Selected text.
+    pub selection: Style,
+    /// The cell under the terminal cursor in input fields.
+    pub caret: Style,
+    /// Borders of the focused view.
+    pub focus_border: Style,
+}
+
+/// Like `Theme::dark`, but text keeps the default colors of the terminal.
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            text: Style::new(),
+            ..Self::dark()
+        }
+    }
+}
+
+impl Theme {
+    /// For terminals with a dark background.
+    pub const fn dark() -> Self {
+        Self {
+            text: Style::new().fg(Color::White),
+            muted: Style::new().fg(Color::DarkGray),
+            selection: Style::new().fg(Color::Black).bg(Color::LightBlue),
+            caret: Style::new(),
+            focus_border: Style::new().fg(Color::LightCyan),
+        }
+    }
+
+    /// For terminals with a light background.
+    pub const fn light() -> Self {
+        Self {
+            text: Style::new().fg(Color::Black),
+            muted: Style::new().fg(Color::DarkGray),
+            selection: Style::new().fg(Color::White).bg(Color::Blue),
+            caret: Style::new(),
+            focus_border: Style::new().fg(Color::Blue),
+        }
+    }
+
+    /// Few colors at full strength, with bold emphasis and a reversed caret.
+    pub const fn high_contrast() -> Self {
+        Self {
+            text: Style::new().fg(Color::Reset),
+            muted: Style::new().fg(Color::Reset),
+            selection: Style::new().fg(Color::Black).bg(Color::Yellow),
+            caret: Style::new().add_modifier(Modifier::REVERSED),
+            focus_border: Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        }
+    }
+}
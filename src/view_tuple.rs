@@ -40,18 +40,28 @@ pub trait ViewTuple: private::Sealed {
 
     /// Call `preferred_size` for each of the `View`s in the `ViewTuple`.
     fn for_each_preferred_size(&self, f: impl FnMut(Option<Size>));
+
+    /// Call `f` on each of the `View`s in the `ViewTuple`.
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View));
+
+    /// Call `f` on each of the `View`s in the `ViewTuple`, mutably.
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View));
 }
 
 impl ViewTuple for ! {
     const LEN: usize = 0;
     fn for_each_render(&self, _frame: &mut Frame, _rect: impl FnMut(usize, Option<Size>) -> Rect) {}
     fn for_each_preferred_size(&self, _f: impl FnMut(Option<Size>)) {}
+    fn for_each_view<'s>(&'s self, _f: &mut dyn FnMut(&'s dyn View)) {}
+    fn for_each_view_mut(&mut self, _f: &mut dyn FnMut(&mut dyn View)) {}
 }
 
 impl ViewTuple for Infallible {
     const LEN: usize = 0;
     fn for_each_render(&self, _frame: &mut Frame, _rect: impl FnMut(usize, Option<Size>) -> Rect) {}
     fn for_each_preferred_size(&self, _f: impl FnMut(Option<Size>)) {}
+    fn for_each_view<'s>(&'s self, _f: &mut dyn FnMut(&'s dyn View)) {}
+    fn for_each_view_mut(&mut self, _f: &mut dyn FnMut(&mut dyn View)) {}
 }
 
 impl<V: View> ViewTuple for V {
@@ -66,12 +76,20 @@ impl<V: View> ViewTuple for V {
     fn for_each_preferred_size(&self, mut f: impl FnMut(Option<Size>)) {
         f(self.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(self);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(self);
+    }
 }
 
 impl ViewTuple for () {
     const LEN: usize = 0;
     fn for_each_render(&self, _frame: &mut Frame, _rect: impl FnMut(usize, Option<Size>) -> Rect) {}
     fn for_each_preferred_size(&self, _f: impl FnMut(Option<Size>)) {}
+    fn for_each_view<'s>(&'s self, _f: &mut dyn FnMut(&'s dyn View)) {}
+    fn for_each_view_mut(&mut self, _f: &mut dyn FnMut(&mut dyn View)) {}
 }
 
 impl<V: View> ViewTuple for (V,) {
@@ -86,6 +104,12 @@ impl<V: View> ViewTuple for (V,) {
     fn for_each_preferred_size(&self, mut f: impl FnMut(Option<Size>)) {
         f(self.0.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+    }
 }
 
 impl<V0: View, V1: View> ViewTuple for (V0, V1) {
@@ -102,6 +126,14 @@ impl<V0: View, V1: View> ViewTuple for (V0, V1) {
         f(self.0.preferred_size());
         f(self.1.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+    }
 }
 
 impl<V0: View, V1: View, V2: View> ViewTuple for (V0, V1, V2) {
@@ -120,6 +152,16 @@ impl<V0: View, V1: View, V2: View> ViewTuple for (V0, V1, V2) {
         f(self.1.preferred_size());
         f(self.1.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+    }
 }
 
 impl<V0: View, V1: View, V2: View, V3: View> ViewTuple for (V0, V1, V2, V3) {
@@ -140,6 +182,18 @@ impl<V0: View, V1: View, V2: View, V3: View> ViewTuple for (V0, V1, V2, V3) {
         f(self.1.preferred_size());
         f(self.2.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+    }
 }
 
 impl<V0: View, V1: View, V2: View, V3: View, V4: View> ViewTuple for (V0, V1, V2, V3, V4) {
@@ -162,6 +216,20 @@ impl<V0: View, V1: View, V2: View, V3: View, V4: View> ViewTuple for (V0, V1, V2
         f(self.2.preferred_size());
         f(self.3.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+        f(&self.4);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+        f(&mut self.4);
+    }
 }
 
 impl<V0: View, V1: View, V2: View, V3: View, V4: View, V5: View> ViewTuple
@@ -188,6 +256,22 @@ impl<V0: View, V1: View, V2: View, V3: View, V4: View, V5: View> ViewTuple
         f(self.3.preferred_size());
         f(self.4.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+        f(&self.4);
+        f(&self.5);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+        f(&mut self.4);
+        f(&mut self.5);
+    }
 }
 
 impl<V0: View, V1: View, V2: View, V3: View, V4: View, V5: View, V6: View> ViewTuple
@@ -216,6 +300,24 @@ impl<V0: View, V1: View, V2: View, V3: View, V4: View, V5: View, V6: View> ViewT
         f(self.4.preferred_size());
         f(self.5.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+        f(&self.4);
+        f(&self.5);
+        f(&self.6);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+        f(&mut self.4);
+        f(&mut self.5);
+        f(&mut self.6);
+    }
 }
 
 impl<V0: View, V1: View, V2: View, V3: View, V4: View, V5: View, V6: View, V7: View> ViewTuple
@@ -246,6 +348,26 @@ impl<V0: View, V1: View, V2: View, V3: View, V4: View, V5: View, V6: View, V7: V
         f(self.5.preferred_size());
         f(self.6.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+        f(&self.4);
+        f(&self.5);
+        f(&self.6);
+        f(&self.7);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+        f(&mut self.4);
+        f(&mut self.5);
+        f(&mut self.6);
+        f(&mut self.7);
+    }
 }
 
 impl<V0: View, V1: View, V2: View, V3: View, V4: View, V5: View, V6: View, V7: View, V8: View>
@@ -278,6 +400,28 @@ impl<V0: View, V1: View, V2: View, V3: View, V4: View, V5: View, V6: View, V7: V
         f(self.6.preferred_size());
         f(self.7.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+        f(&self.4);
+        f(&self.5);
+        f(&self.6);
+        f(&self.7);
+        f(&self.8);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+        f(&mut self.4);
+        f(&mut self.5);
+        f(&mut self.6);
+        f(&mut self.7);
+        f(&mut self.8);
+    }
 }
 
 impl<
@@ -322,6 +466,30 @@ impl<
         f(self.7.preferred_size());
         f(self.8.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+        f(&self.4);
+        f(&self.5);
+        f(&self.6);
+        f(&self.7);
+        f(&self.8);
+        f(&self.9);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+        f(&mut self.4);
+        f(&mut self.5);
+        f(&mut self.6);
+        f(&mut self.7);
+        f(&mut self.8);
+        f(&mut self.9);
+    }
 }
 
 impl<
@@ -369,6 +537,32 @@ impl<
         f(self.8.preferred_size());
         f(self.9.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+        f(&self.4);
+        f(&self.5);
+        f(&self.6);
+        f(&self.7);
+        f(&self.8);
+        f(&self.9);
+        f(&self.10);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+        f(&mut self.4);
+        f(&mut self.5);
+        f(&mut self.6);
+        f(&mut self.7);
+        f(&mut self.8);
+        f(&mut self.9);
+        f(&mut self.10);
+    }
 }
 
 impl<
@@ -419,4 +613,32 @@ impl<
         f(self.9.preferred_size());
         f(self.10.preferred_size());
     }
+    fn for_each_view<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.0);
+        f(&self.1);
+        f(&self.2);
+        f(&self.3);
+        f(&self.4);
+        f(&self.5);
+        f(&self.6);
+        f(&self.7);
+        f(&self.8);
+        f(&self.9);
+        f(&self.10);
+        f(&self.11);
+    }
+    fn for_each_view_mut(&mut self, f: &mut dyn FnMut(&mut dyn View)) {
+        f(&mut self.0);
+        f(&mut self.1);
+        f(&mut self.2);
+        f(&mut self.3);
+        f(&mut self.4);
+        f(&mut self.5);
+        f(&mut self.6);
+        f(&mut self.7);
+        f(&mut self.8);
+        f(&mut self.9);
+        f(&mut self.10);
+        f(&mut self.11);
+    }
 }
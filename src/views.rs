@@ -1,19 +1,28 @@
 #![allow(dead_code)]
 
 use std::{
+    any::Any,
     borrow::Cow,
     collections::HashMap,
     fmt::{self, Debug},
-    io,
-    sync::{Arc, Mutex, Weak},
+    mem, ops,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc, Arc, Mutex, MutexGuard, PoisonError, Weak,
+    },
+    time::{Duration, Instant},
 };
 
 use derive_more::From;
 
 use ratatui::{
     backend::Backend,
-    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    buffer::Buffer,
+    crossterm::{
+        cursor::SetCursorStyle,
+        event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind},
+    },
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style, Styled},
     text::{Line, Span, Text},
     widgets::{self, Block, Wrap},
@@ -21,8 +30,12 @@ use ratatui::{
 };
 
 use super::{
-    input_field::{self, Cursor, InputFieldContent},
+    handle::{ScreenHandle, ScreenMessage, Update, Wakeup},
+    input_field::{Cursor, InputFieldContent},
+    keymap::{Interrupted, KeyChord, KeyMatch, Keymap, ParseKeyError},
+    theme::Theme,
     view_tuple::ViewTuple,
+    Error, Result,
 };
 
 /// `'a` for allowing to borrow from a data source.
@@ -31,6 +44,36 @@ pub struct Screen<'a, V: View + 'a> {
     root_view: V,
     dynamic_sites: Vec<ViewCellWeakRef<'a>>,
     dynamic_site_tags: HashMap<Cow<'a, str>, ViewCellWeakRef<'a>>,
+    /// Indices into `dynamic_sites`, sorted by tab index.
+    tab_order: Vec<usize>,
+    /// Inverse of `tab_order`, maps an index into `dynamic_sites` to its position in `tab_order`.
+    tab_position: Vec<usize>,
+    /// Index into `dynamic_sites` of the focused view.
+    focused_idx: Option<usize>,
+    /// Index into `dynamic_sites` of the last focused view in each focus group.
+    group_focus: HashMap<Cow<'a, str>, usize>,
+    /// Keybindings consulted before the focused view receives a key event.
+    keymap: Keymap<Command<'a, V>>,
+    /// Key events of the pending keys of `keymap`, held back from the focused view until it is
+    /// known whether they complete a key sequence.
+    pending_key_events: Vec<KeyEvent>,
+    /// Keybindings consulted after the focused view ignored a key event.
+    fallback_keymap: Keymap<Command<'a, V>>,
+    /// For creating `ScreenHandle`s.
+    sender: mpsc::Sender<ScreenMessage>,
+    /// Messages from `ScreenHandle`s.
+    receiver: Arc<Mutex<mpsc::Receiver<ScreenMessage>>>,
+    wakeup: Arc<Wakeup>,
+    /// Running timers of views, only views with a timer are in here.
+    timers: Vec<ScheduledTimer>,
+    /// Shared with all `ViewCell`s of this screen.
+    render_state: Arc<RenderState>,
+    /// View requested to be edited in an external editor, see `Screen::request_external_edit`.
+    external_edit: Option<ViewCellWeakRef<'a>>,
+    theme: Theme,
+    /// Time used instead of the real time for key timeouts and timers, while replaying a
+    /// recording, see `Screen::now`.
+    virtual_now: Option<Instant>,
 }
 
 /// `'a` for allowing to borrow from a data source.
@@ -38,6 +81,10 @@ pub struct Screen<'a, V: View + 'a> {
 pub struct ScreenBuilder<'a> {
     dynamic_sites: Vec<ViewCellWeakRef<'a>>,
     dynamic_site_tags: HashMap<Cow<'a, str>, ViewCellWeakRef<'a>>,
+    initial_focus: Option<ViewCellWeakRef<'a>>,
+    /// Shared with the `ViewCell`s created by this builder, becomes `Screen::render_state`.
+    render_state: Arc<RenderState>,
+    theme: Theme,
 }
 
 impl<'a> ScreenBuilder<'a> {
@@ -45,17 +92,74 @@ impl<'a> ScreenBuilder<'a> {
         Self::default()
     }
 
-    pub fn finish<V: View>(self, root_view: V) -> Screen<'a, V> {
-        Screen {
+    pub fn finish<V: View>(self, mut root_view: V) -> Screen<'a, V> {
+        root_view.set_theme(&self.theme);
+        let (sender, receiver) = mpsc::channel();
+        let mut screen = Screen {
             root_view,
             dynamic_sites: self.dynamic_sites,
             dynamic_site_tags: self.dynamic_site_tags,
+            tab_order: Vec::new(),
+            tab_position: Vec::new(),
+            focused_idx: None,
+            group_focus: HashMap::new(),
+            keymap: Keymap::new(),
+            pending_key_events: Vec::new(),
+            fallback_keymap: Keymap::new(),
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            wakeup: Arc::default(),
+            timers: Vec::new(),
+            render_state: self.render_state,
+            external_edit: None,
+            theme: self.theme,
+            virtual_now: None,
+        };
+        screen.mark_dirty();
+        screen.bind_default_keys();
+        screen.refresh_tab_order();
+        let now = screen.now();
+        for idx in 0..screen.dynamic_sites.len() {
+            let Some(view) = screen.site(idx) else {
+                continue;
+            };
+            let Ok(mut inner) = view.lock() else {
+                continue;
+            };
+            inner.view.set_theme(&screen.theme);
+            let timer = inner.timer.take();
+            drop(inner);
+            if let Some(timer) = timer {
+                screen.timers.push(ScheduledTimer {
+                    idx,
+                    timer,
+                    start: now,
+                });
+            }
+        }
+        if let Some(initial_focus) = self.initial_focus.and_then(|weak| weak.upgrade()) {
+            screen.focus(&initial_focus);
         }
+        screen
+    }
+
+    /// Set the view that is focused when the screen is built.
+    /// Without an initial focus, no view is focused until the user presses Tab.
+    pub fn initial_focus(&mut self, view: &ViewCell<'a>) -> &mut Self {
+        self.initial_focus = Some(view.downgrade());
+        self
+    }
+
+    /// Set the theme of the screen, see `Screen::set_theme`.
+    /// Defaults to `Theme::default`, which keeps the default colors of the terminal for text.
+    pub fn theme(&mut self, theme: Theme) -> &mut Self {
+        self.theme = theme;
+        self
     }
 
     /// Wrap a `MutView` into a `ViewCell`, which implements non-mut `View`.
     pub fn view_cell(&mut self, view: impl MutView + Send + 'a) -> ViewCell<'a> {
-        let view = ViewCell::new(false, view);
+        let view = ViewCell::new(false, self.render_state.clone(), view);
         self.dynamic_sites.push(view.downgrade());
         view
     }
@@ -66,7 +170,7 @@ impl<'a> ScreenBuilder<'a> {
         tag: impl Into<Cow<'a, str>>,
         view: impl MutView + Send + 'a,
     ) -> ViewCell<'a> {
-        let view = ViewCell::new(false, view);
+        let view = ViewCell::new(false, self.render_state.clone(), view);
         self.dynamic_site_tags.insert(tag.into(), view.downgrade());
         self.dynamic_sites.push(view.downgrade());
         view
@@ -81,39 +185,192 @@ impl<'a, V: View + 'a> Screen<'a, V> {
         ScreenBuilder::default().finish(root_view)
     }
 
-    pub fn render<B: Backend>(&self, terminal: &mut Terminal<B>) -> io::Result<()> {
-        terminal.autoresize()?;
-        let mut frame = terminal.get_frame();
-        let area = frame.area();
-        self.root_view.render(&mut frame, area);
-        terminal.hide_cursor()?;
-        terminal.flush()?;
-        terminal.swap_buffers();
-        terminal.backend_mut().flush()?;
+    /// Render the screen, showing the terminal cursor where the focused view placed it (see
+    /// `MutView::render`).
+    /// The cursor style is not applied here, since not every backend supports it, see
+    /// `Screen::cursor_style` and `CursorStyleBackend`.
+    /// Fails with `Error::PoisonedLock` if a view of the screen panicked, see `ViewCell`.
+    pub fn render<B: Backend>(&self, terminal: &mut Terminal<B>) -> Result<()> {
+        let is_poisoned = self
+            .dynamic_sites
+            .iter()
+            .filter_map(ViewCellWeakRef::upgrade)
+            .any(|view| view.inner.is_poisoned());
+        if is_poisoned {
+            return Err(Error::PoisonedLock);
+        }
+        // Views that are not rendered this time must not keep receiving mouse events or focus by
+        // direction through the area they were rendered in before, see `ViewCell::last_area`.
+        self.render_state.renders.fetch_add(1, Ordering::Relaxed);
+        self.render_state.dirty.store(false, Ordering::Relaxed);
+        terminal.draw(|frame| self.root_view.render(frame, frame.area()))?;
         Ok(())
     }
 
+    /// Style of the terminal cursor requested by the focused view, see `MutView::cursor_style`.
+    /// `EventLoop` applies it after rendering, see `CursorStyleBackend`.
+    pub fn cursor_style(&self) -> CursorStyle {
+        self.focused()
+            .and_then(|view| Some(view.lock().ok()?.view.cursor_style()))
+            .unwrap_or(CursorStyle::Default)
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Change the theme, passing it into all views of the screen (see `View::set_theme` and
+    /// `MutView::set_theme`).
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.root_view.set_theme(&theme);
+        for view in self
+            .dynamic_sites
+            .iter()
+            .filter_map(ViewCellWeakRef::upgrade)
+        {
+            if let Ok(mut inner) = view.lock() {
+                inner.view.set_theme(&theme);
+            }
+        }
+        self.mark_dirty();
+    }
+
+    /// If anything changed since the last render, so the screen needs to be rendered again.
+    /// Views are marked as changed when they handle an event, gain or lose focus, fire a timer,
+    /// get updated by a `ScreenHandle`, or are mutated through `ViewCell::inspect`. The screen is
+    /// also marked as changed when the pending keys (see `Screen::pending_keys`) change.
+    pub fn needs_render(&self) -> bool {
+        self.render_state.dirty.load(Ordering::Relaxed)
+    }
+
+    /// Mark the screen as changed, so it is rendered again.
+    /// Needed after changing data that views borrow from, which the screen can not notice.
+    pub fn mark_dirty(&self) {
+        self.render_state.mark_dirty();
+    }
+
+    /// The current time for key timeouts and timers.
+    /// While replaying a recording, this is the time of the replayed entry instead of the real
+    /// time, see `Screen::set_virtual_now`.
+    fn now(&self) -> Instant {
+        self.virtual_now.unwrap_or_else(Instant::now)
+    }
+
+    /// Use `now` as the current time for key timeouts and timers, or with `None`, the real time
+    /// again.
+    pub(crate) fn set_virtual_now(&mut self, now: Option<Instant>) {
+        self.virtual_now = now;
+    }
+
+    /// A handle for updating views of this screen and waking up the event loop from other
+    /// threads.
+    pub fn handle(&self) -> ScreenHandle {
+        ScreenHandle::new(self.sender.clone(), self.wakeup.clone())
+    }
+
+    pub(crate) fn wakeup(&self) -> Arc<Wakeup> {
+        self.wakeup.clone()
+    }
+
+    /// Wait for the next message from `ScreenHandle`s, until `deadline` or indefinitely.
+    pub(crate) fn recv_message(&self, deadline: Option<Instant>) -> Option<ScreenMessage> {
+        // Receiving does not panic, the receiver can not be in an inconsistent state.
+        let receiver = self.receiver.lock().unwrap_or_else(PoisonError::into_inner);
+        match deadline {
+            Some(deadline) => receiver
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .ok(),
+            None => receiver.recv().ok(),
+        }
+    }
+
+    /// The next message from `ScreenHandle`s, if there is any.
+    pub(crate) fn try_recv_message(&self) -> Option<ScreenMessage> {
+        self.receiver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_recv()
+            .ok()
+    }
+
+    /// Run an update from `ScreenHandle::update_tagged`.
+    pub(crate) fn apply_update(&mut self, tag: &str, update: Update) {
+        let Some(view) = self
+            .dynamic_site_tags
+            .get(tag)
+            .and_then(|weak| weak.upgrade())
+        else {
+            return;
+        };
+        let Ok(mut inner) = view.lock() else {
+            return;
+        };
+        inner.mark_dirty();
+        update(inner.view.as_mut());
+    }
+
+    /// Run the updates queued by `ScreenHandle`s, and pass the events they sent into the screen.
+    /// Returns the messages posted by `ScreenHandle::post`.
+    /// `EventLoop` does this automatically, this is only needed for custom event loops.
+    pub fn process_messages(&mut self) -> Vec<Box<dyn Any + Send>> {
+        let mut posts = Vec::new();
+        while let Some(message) = self.try_recv_message() {
+            match message {
+                ScreenMessage::Update { tag, update } => self.apply_update(&tag, update),
+                ScreenMessage::SetTimer { tag, timer } => {
+                    self.set_timer_tagged(&tag, timer);
+                }
+                ScreenMessage::Post(post) => posts.push(post),
+                ScreenMessage::Wake => self.mark_dirty(),
+                ScreenMessage::Event(event) => {
+                    self.handle_event(event);
+                }
+                // Only for event loops.
+                ScreenMessage::EventsReady | ScreenMessage::Suspend | ScreenMessage::Continued => {}
+            }
+        }
+        posts
+    }
+
+    /// Create a `ViewCell` of this screen, with the theme of this screen.
+    fn new_view_cell(&self, mut view: impl MutView + Send + 'a) -> ViewCell<'a> {
+        view.set_theme(&self.theme);
+        ViewCell::new(false, self.render_state.clone(), view)
+    }
+
     /// Wrap a `MutView` into a `ViewCell`, which implements non-mut `View`.
     /// This function is for mutating views in a screen after it was built, for creating a
     /// `ViewCell` during building of the screen, use `ScreenBuilder`.
     pub fn view_cell(&mut self, view: impl MutView + Send + 'a) -> ViewCell<'a> {
-        let dynamic_site = ViewCell::new(false, view);
+        let dynamic_site = self.new_view_cell(view);
         self.dynamic_sites.push(dynamic_site.downgrade());
+        self.refresh_tab_order();
+        self.mark_dirty();
         dynamic_site
     }
 
     /// Wrap a `MutView` into a `ViewCell`, which implements non-mut `View`, and tag it.
     /// This function is for mutating views in a screen after it was built, for creating a
     /// `ViewCell` during building of the screen, use `ScreenBuilder`.
-    pub fn tagged_view_cell(&mut self, view: impl MutView + Send + 'a) -> ViewCell<'a> {
-        let dynamic_site = ViewCell::new(false, view);
+    pub fn tagged_view_cell(
+        &mut self,
+        tag: impl Into<Cow<'a, str>>,
+        view: impl MutView + Send + 'a,
+    ) -> ViewCell<'a> {
+        let dynamic_site = self.new_view_cell(view);
+        self.dynamic_site_tags
+            .insert(tag.into(), dynamic_site.downgrade());
         self.dynamic_sites.push(dynamic_site.downgrade());
+        self.refresh_tab_order();
+        self.mark_dirty();
         dynamic_site
     }
 
     /// If multiple views were tagged the same, only one of them is inspected, randomly.
     /// Returns `None` if no view of such tag exists.
     /// If more than one view of such tag exist, one of the views would be provided at random.
+    /// Fails like `ViewCell::inspect`.
     ///
     /// # Safety
     /// `V` must be of the correct type that the value was initialized with.
@@ -121,107 +378,1005 @@ impl<'a, V: View + 'a> Screen<'a, V> {
         &self,
         tag: &str,
         f: impl FnOnce(&mut V2) -> T,
-    ) -> Option<T> {
-        let view = self.dynamic_site_tags.get(tag)?;
-        Some(view.upgrade().unwrap().inspect::<_, V2>(f))
+    ) -> Result<Option<T>> {
+        let Some(view) = self
+            .dynamic_site_tags
+            .get(tag)
+            .and_then(ViewCellWeakRef::upgrade)
+        else {
+            return Ok(None);
+        };
+        unsafe { view.inspect::<_, V2>(f) }.map(Some)
     }
 
-    /// Switch focus to the next focusable view.
-    /// A focusable view is an `View` with its `is_focusable` returning `true`.
-    pub fn focus_next(&mut self) {
-        // FIXME: optimize this by keeping track the of index of the focused view.
-        if self.dynamic_sites.is_empty() {
+    /// Upgrade the dynamic site at `idx`, if it is still alive.
+    fn site(&self, idx: usize) -> Option<ViewCell<'a>> {
+        self.dynamic_sites.get(idx)?.upgrade()
+    }
+
+    /// If the dynamic site at `idx` is alive, not poisoned and focusable.
+    fn site_is_focusable(&self, idx: usize) -> bool {
+        self.site(idx)
+            .is_some_and(|vc| vc.lock().is_ok_and(|inner| inner.view.is_focusable()))
+    }
+
+    /// The focus group of the dynamic site at `idx`, if it is alive, not poisoned and in a group.
+    fn site_group(&self, idx: usize) -> Option<Cow<'a, str>> {
+        self.site(idx)?.lock().ok()?.focus_group.clone()
+    }
+
+    /// Re-sort the tab order according to the tab indices of the views.
+    /// Views with the same tab index are ordered in the order they were created.
+    /// This is done automatically when the screen is built or when a view is added to it, call it
+    /// manually only after changing the tab index or focus group of an existing view.
+    pub fn refresh_tab_order(&mut self) {
+        let mut tab_order: Vec<(i32, usize)> = (0..self.dynamic_sites.len())
+            .map(|idx| {
+                let tab_index = self
+                    .site(idx)
+                    .and_then(|vc| Some(vc.lock().ok()?.tab_index))
+                    .unwrap_or(0);
+                (tab_index, idx)
+            })
+            .collect();
+        tab_order.sort_by_key(|&(tab_index, _)| tab_index);
+        self.tab_order = tab_order.into_iter().map(|(_, idx)| idx).collect();
+        self.tab_position = vec![0; self.tab_order.len()];
+        for (position, &idx) in self.tab_order.iter().enumerate() {
+            self.tab_position[idx] = position;
+        }
+    }
+
+    /// Move focus to the dynamic site at `idx` (or unfocus everything if `None`), calling
+    /// `on_unfocus` and `on_focus` accordingly.
+    /// No-op if `idx` is already the focused site.
+    fn set_focus(&mut self, idx: Option<usize>) {
+        if idx == self.focused_idx {
             return;
         }
+        if let Some((old_idx, old)) = self.focused_idx.and_then(|i| Some((i, self.site(i)?))) {
+            if let Ok(mut old) = old.lock() {
+                old.mark_dirty();
+                old.is_focused = false;
+                self.with_site_timer(old_idx, |timer| old.view.on_unfocus(timer));
+            }
+        }
+        self.focused_idx = idx;
+        if let Some((idx, new)) = idx.and_then(|i| Some((i, self.site(i)?))) {
+            let Ok(mut new) = new.lock() else {
+                return;
+            };
+            new.mark_dirty();
+            new.is_focused = true;
+            self.with_site_timer(idx, |timer| new.view.on_focus(timer));
+            if let Some(group) = new.focus_group.clone() {
+                self.group_focus.insert(group, idx);
+            }
+        }
+    }
 
-        // Unfocus the focused view.
-        let (idx, focused_view) = self
-            .dynamic_sites
-            .iter()
-            .enumerate()
-            .find(|(_, weak_mv)| {
-                weak_mv
-                    .upgrade()
-                    .is_some_and(|vc| vc.inner.lock().unwrap().is_focused)
+    /// Walk through the tab order, starting at position `start`, either forward or backward and
+    /// wrapping around.
+    /// Returns index of the first dynamic site that satisfies `predicate`.
+    fn walk_tab_order(
+        &self,
+        start: usize,
+        forward: bool,
+        mut predicate: impl FnMut(usize) -> bool,
+    ) -> Option<usize> {
+        let len = self.tab_order.len();
+        (0..len)
+            .map(|step| match forward {
+                true => (start + step) % len,
+                false => (start + len - step) % len,
             })
-            .map(|(i, weak_mv)| (i, weak_mv.clone()))
-            .unwrap_or_default();
-        let start_idx = match focused_view.upgrade() {
-            Some(focused_view) => {
-                let mut focused_view = focused_view.inner.lock().unwrap();
-                focused_view.is_focused = false;
-                focused_view.view.on_unfocus();
-                idx + 1
+            .map(|position| self.tab_order[position])
+            .find(|&idx| predicate(idx))
+    }
+
+    /// When focus moves into a focus group from outside, the view in that group that was last
+    /// focused is focused instead of the one Tab landed on.
+    fn enter_group(&self, idx: usize) -> usize {
+        let Some(group) = self.site_group(idx) else {
+            return idx;
+        };
+        match self.group_focus.get(&group) {
+            Some(&last_focused)
+                if self.site_is_focusable(last_focused)
+                    && self.site_group(last_focused).as_ref() == Some(&group) =>
+            {
+                last_focused
             }
-            None => 0,
+            _ => idx,
+        }
+    }
+
+    /// Focus the next focusable view (or focus group) in `forward` or backward direction.
+    fn focus_step(&mut self, forward: bool) {
+        let len = self.tab_order.len();
+        if len == 0 {
+            return;
+        }
+        let current_group = self.focused_idx.and_then(|i| self.site_group(i));
+        let start = match (self.focused_idx, forward) {
+            (Some(idx), true) => self.tab_position[idx] + 1,
+            (Some(idx), false) => self.tab_position[idx] + len - 1,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+        let next = self.walk_tab_order(start, forward, |idx| {
+            self.site_is_focusable(idx)
+                && (current_group.is_none() || self.site_group(idx) != current_group)
+        });
+        if let Some(next) = next {
+            self.set_focus(Some(self.enter_group(next)));
+        }
+    }
+
+    /// Switch focus to the next focusable view in tab order.
+    /// A focusable view is an `View` with its `is_focusable` returning `true`.
+    /// A focus group counts as one stop in tab order, see `ViewCell::focus_group`.
+    pub fn focus_next(&mut self) {
+        self.focus_step(true);
+    }
+
+    /// Switch focus to the previous focusable view in tab order.
+    /// A focusable view is an `View` with its `is_focusable` returning `true`.
+    /// A focus group counts as one stop in tab order, see `ViewCell::focus_group`.
+    pub fn focus_prev(&mut self) {
+        self.focus_step(false);
+    }
+
+    /// Switch focus to the first focusable view in tab order.
+    pub fn focus_first(&mut self) {
+        if self.tab_order.is_empty() {
+            return;
+        }
+        if let Some(first) = self.walk_tab_order(0, true, |idx| self.site_is_focusable(idx)) {
+            self.set_focus(Some(self.enter_group(first)));
+        }
+    }
+
+    /// Switch focus to the last focusable view in tab order.
+    pub fn focus_last(&mut self) {
+        let len = self.tab_order.len();
+        if len == 0 {
+            return;
+        }
+        if let Some(last) = self.walk_tab_order(len - 1, false, |idx| self.site_is_focusable(idx)) {
+            self.set_focus(Some(self.enter_group(last)));
+        }
+    }
+
+    /// Move focus within the focus group of the focused view, in `forward` or backward direction.
+    fn focus_step_in_group(&mut self, forward: bool) {
+        let Some(focused_idx) = self.focused_idx else {
+            return;
+        };
+        let Some(group) = self.site_group(focused_idx) else {
+            return;
         };
+        let len = self.tab_order.len();
+        let start = match forward {
+            true => self.tab_position[focused_idx] + 1,
+            false => self.tab_position[focused_idx] + len - 1,
+        };
+        let next = self.walk_tab_order(start, forward, |idx| {
+            self.site_is_focusable(idx) && self.site_group(idx).as_ref() == Some(&group)
+        });
+        self.set_focus(next);
+    }
+
+    /// Switch focus to the next focusable view in the same focus group as the focused view.
+    /// No-op if the focused view is not in a focus group.
+    pub fn focus_next_in_group(&mut self) {
+        self.focus_step_in_group(true);
+    }
 
-        // Focus the next focusable.
-        let next_focusable = self.dynamic_sites[start_idx..]
+    /// Switch focus to the previous focusable view in the same focus group as the focused view.
+    /// No-op if the focused view is not in a focus group.
+    pub fn focus_prev_in_group(&mut self) {
+        self.focus_step_in_group(false);
+    }
+
+    /// Switch focus to the nearest focusable view in `direction`, judging by the areas the views
+    /// were rendered in by the last `Screen::render`.
+    /// Views that were not rendered then are never chosen.
+    /// If no view is focused, or the focused view was not rendered then, focus the first view in
+    /// tab order instead.
+    pub fn focus_direction(&mut self, direction: FocusDirection) {
+        let Some(from) = self.focused().and_then(|vc| vc.last_area()) else {
+            self.focus_first();
+            return;
+        };
+        let nearest = (0..self.dynamic_sites.len())
+            .filter(|&idx| Some(idx) != self.focused_idx && self.site_is_focusable(idx))
+            .filter_map(|idx| Some((idx, self.site(idx)?.last_area()?)))
+            .filter_map(|(idx, to)| Some((direction.distance(from, to)?, idx)))
+            .min();
+        if let Some((_, idx)) = nearest {
+            self.set_focus(Some(idx));
+        }
+    }
+
+    /// Index into `dynamic_sites` of a view.
+    fn site_index_of(&self, view: &ViewCellWeakRef<'a>) -> Option<usize> {
+        self.dynamic_sites
             .iter()
-            .chain(
-                self.dynamic_sites
-                    .get(..start_idx.wrapping_sub(1))
-                    .unwrap_or(&[])
-                    .iter(),
-            )
-            .find(|&weak_mv| {
-                weak_mv
-                    .upgrade()
-                    .is_some_and(|vc| vc.inner.lock().unwrap().view.is_focusable())
-            })
-            .map(|weak_mv| weak_mv.upgrade().unwrap());
-        if let Some(next_focusable) = next_focusable {
-            let mut next_focusable = next_focusable.inner.lock().unwrap();
-            next_focusable.is_focused = true;
-            next_focusable.view.on_focus();
+            .position(|site| site.is(view.clone()))
+    }
+
+    /// Switch focus to a specific view.
+    /// Returns `false` (and leaves focus unchanged) if the view does not belong to this screen or
+    /// is not focusable.
+    pub fn focus(&mut self, view: &ViewCell<'a>) -> bool {
+        let Some(idx) = self.site_index_of(&view.downgrade()) else {
+            return false;
+        };
+        if !self.site_is_focusable(idx) {
+            return false;
+        }
+        self.set_focus(Some(idx));
+        true
+    }
+
+    /// Switch focus to the view of a tag.
+    /// If more than one view of such tag exist, one of the views would be focused at random.
+    /// Returns `false` (and leaves focus unchanged) if no view of such tag exists or the view is
+    /// not focusable.
+    pub fn focus_tag(&mut self, tag: &str) -> bool {
+        let Some(view) = self
+            .dynamic_site_tags
+            .get(tag)
+            .and_then(|weak| weak.upgrade())
+        else {
+            return false;
+        };
+        self.focus(&view)
+    }
+
+    /// Unfocus the focused view, if any.
+    pub fn blur(&mut self) {
+        self.set_focus(None);
+    }
+
+    /// Request editing the text of the focused view in an external editor, see
+    /// `MutView::input_field_content_mut`. `EventLoop` runs the editor before the next render,
+    /// custom event loops use `Screen::take_external_edit`.
+    /// Returns `false` if no view is focused, or the focused view has no text to edit.
+    pub fn request_external_edit(&mut self) -> bool {
+        let Some(view) = self.focused() else {
+            return false;
+        };
+        let has_text = view
+            .lock()
+            .is_ok_and(|mut inner| inner.view.input_field_content_mut().is_some());
+        if has_text {
+            self.external_edit = Some(view.downgrade());
         }
+        has_text
+    }
+
+    /// If an external edit was requested and not taken yet.
+    pub(crate) fn has_external_edit(&self) -> bool {
+        self.external_edit.is_some()
+    }
+
+    /// Take the external edit requested by `Screen::request_external_edit`, if the view still
+    /// exists.
+    pub fn take_external_edit(&mut self) -> Option<ExternalEdit<'a>> {
+        let view = self.external_edit.take()?.upgrade()?;
+        let text = view
+            .lock()
+            .ok()?
+            .view
+            .input_field_content_mut()?
+            .text()
+            .to_owned();
+        Some(ExternalEdit { view, text })
+    }
+
+    /// If the focused view is in a focus group.
+    fn focused_is_in_group(&self) -> bool {
+        self.focused_idx
+            .is_some_and(|idx| self.site_group(idx).is_some())
     }
 
     /// Returns the view currently in focus in the form of a `ViewCell`.
     /// Returns `None` if no view is in focus (including the situation where a view was focused but
     /// was since deleted).
     pub fn focused(&self) -> Option<ViewCell<'a>> {
-        for mv in &self.dynamic_sites {
-            let mv = mv.upgrade()?;
-            if mv.inner.lock().unwrap().is_focused {
-                return Some(mv);
+        self.site(self.focused_idx?)
+    }
+
+    /// Keybindings every screen starts with:
+    /// - `Tab`, `S-Tab`: `Action::FocusNext`, `Action::FocusPrev`.
+    /// - `M-Left`, `M-Right`, `M-Up`, `M-Down`: `Action::FocusDirection`.
+    /// - `Left`, `Up`, `Right`, `Down`: `Action::FocusPrevInGroup`, `Action::FocusNextInGroup`.
+    fn bind_default_keys(&mut self) {
+        let default_keys = [
+            ("Tab", Action::FocusNext),
+            ("S-Tab", Action::FocusPrev),
+            ("M-Left", Action::FocusDirection(FocusDirection::Left)),
+            ("M-Right", Action::FocusDirection(FocusDirection::Right)),
+            ("M-Up", Action::FocusDirection(FocusDirection::Up)),
+            ("M-Down", Action::FocusDirection(FocusDirection::Down)),
+            ("Left", Action::FocusPrevInGroup),
+            ("Up", Action::FocusPrevInGroup),
+            ("Right", Action::FocusNextInGroup),
+            ("Down", Action::FocusNextInGroup),
+        ];
+        for (keys, action) in default_keys {
+            self.bind_action(keys, action)
+                .expect("default keys are valid");
+        }
+    }
+
+    /// Bind a key sequence (e.g. `"C-s"`, `"C-x C-s"`, see `Keymap` for the syntax) to a
+    /// callback.
+    /// The callback is run before the focused view receives the key event, and the key event
+    /// does not reach the focused view. Key events that are part of a key sequence still being
+    /// typed are held back, and reach the focused view if the key sequence is not completed.
+    /// Replaces the previous binding of the same key sequence, if any.
+    ///
+    /// If the callback passes an event that triggers it again into `Screen::handle_event`, the
+    /// inner event is handled as if the callback did not apply (see `Screen::bind_action`).
+    pub fn bind(
+        &mut self,
+        keys: &str,
+        callback: impl FnMut(&mut Screen<'a, V>) + Send + 'a,
+    ) -> Result<(), ParseKeyError> {
+        self.keymap
+            .bind(keys, Command::Callback(Arc::new(Mutex::new(callback))))
+    }
+
+    /// Bind a key sequence (e.g. `"C-s"`, `"g g"`, see `Keymap` for the syntax) to a callback.
+    /// Unlike `Screen::bind`, the callback is only run if the focused view ignored the key events.
+    pub fn bind_fallback(
+        &mut self,
+        keys: &str,
+        callback: impl FnMut(&mut Screen<'a, V>) + Send + 'a,
+    ) -> Result<(), ParseKeyError> {
+        self.fallback_keymap
+            .bind(keys, Command::Callback(Arc::new(Mutex::new(callback))))
+    }
+
+    /// Bind a key sequence (e.g. `"C-s"`, `"C-x C-s"`, see `Keymap` for the syntax) to a
+    /// built-in action.
+    /// Like `Screen::bind`, the action is performed before the focused view receives the key
+    /// event. If the action does not apply (see `Action`), the focused view receives the key event
+    /// as usual.
+    /// Replaces the previous binding of the same key sequence, if any.
+    pub fn bind_action(&mut self, keys: &str, action: Action) -> Result<(), ParseKeyError> {
+        self.keymap.bind(keys, Command::Action(action))
+    }
+
+    /// Remove bindings of a key sequence, including default ones (see `Screen::bind_action`).
+    pub fn unbind(&mut self, keys: &str) -> Result<(), ParseKeyError> {
+        self.keymap.unbind(keys)?;
+        self.fallback_keymap.unbind(keys)?;
+        Ok(())
+    }
+
+    /// Set the leader key, which `<leader>` stands for in key sequences (e.g. `"Space"`).
+    /// Must be set before binding key sequences using `<leader>`.
+    pub fn set_leader(&mut self, leader: &str) -> Result<(), ParseKeyError> {
+        let leader: KeyChord = leader.parse()?;
+        self.keymap.set_leader(leader);
+        self.fallback_keymap.set_leader(leader);
+        Ok(())
+    }
+
+    /// Set how long to wait for the next key in a key sequence, defaults to one second.
+    pub fn set_key_timeout(&mut self, timeout: Duration) {
+        self.keymap.set_timeout(timeout);
+        self.fallback_keymap.set_timeout(timeout);
+    }
+
+    /// Keys typed so far of a key sequence that is not complete yet, for displaying in a status
+    /// view.
+    pub fn pending_keys(&self) -> &[KeyChord] {
+        match self.keymap.pending() {
+            [] => self.fallback_keymap.pending(),
+            pending => pending,
+        }
+    }
+
+    /// When the pending keys (see `Screen::pending_keys`) time out, if there are any.
+    /// Event loops should call `Screen::handle_key_timeout` at that time.
+    pub fn key_timeout_deadline(&self) -> Option<Instant> {
+        match (self.keymap.deadline(), self.fallback_keymap.deadline()) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        }
+    }
+
+    /// Handle pending keys (see `Screen::pending_keys`) that have timed out.
+    /// If the pending keys start with a bound key sequence (e.g. `"g"` while `"g g"` is also
+    /// bound), run what it is bound to. Keys that are not bound are passed to the focused view.
+    pub fn handle_key_timeout(&mut self) -> EventResult {
+        self.handle_key_timeout_at(self.now())
+    }
+
+    fn handle_key_timeout_at(&mut self, now: Instant) -> EventResult {
+        let mut result = EventResult::Ignored;
+        if let Some(interrupted) = self.keymap.take_timed_out(now) {
+            self.mark_dirty();
+            let key_events = mem::take(&mut self.pending_key_events);
+            result = self.replay_interrupted(interrupted, &key_events, now);
+        }
+        if let Some(interrupted) = self.fallback_keymap.take_timed_out(now) {
+            self.mark_dirty();
+            result = self.replay_fallback_interrupted(interrupted, now);
+        }
+        result
+    }
+
+    /// Start, replace, or with `None`, stop the timer of a view.
+    /// Returns `false` if the view is not in this screen.
+    /// Views can also start timers from their event hooks, see `MutView::on_key_event`.
+    pub fn set_timer(&mut self, view: &ViewCell<'a>, timer: Option<Timer>) -> bool {
+        let Some(idx) = self.site_index_of(&view.downgrade()) else {
+            return false;
+        };
+        self.set_site_timer(idx, timer);
+        true
+    }
+
+    /// Start, replace, or with `None`, stop the timer of the view at `idx` of `dynamic_sites`.
+    fn set_site_timer(&mut self, idx: usize, timer: Option<Timer>) {
+        self.timers.retain(|scheduled| scheduled.idx != idx);
+        if let Some(timer) = timer {
+            self.timers.push(ScheduledTimer {
+                idx,
+                timer,
+                start: self.now(),
+            });
+        }
+    }
+
+    /// Call an event hook of the view at `idx` of `dynamic_sites` with its running timer, and
+    /// start the timer the hook left if it changed it.
+    fn with_site_timer<R>(&mut self, idx: usize, hook: impl FnOnce(&mut Option<Timer>) -> R) -> R {
+        let running = self
+            .timers
+            .iter()
+            .find(|scheduled| scheduled.idx == idx)
+            .map(|scheduled| scheduled.timer);
+        let mut timer = running;
+        let result = hook(&mut timer);
+        if timer != running {
+            self.set_site_timer(idx, timer);
+        }
+        result
+    }
+
+    /// Like `Screen::set_timer`, for the view of a tag.
+    /// Returns `false` if no view of such tag exists.
+    pub fn set_timer_tagged(&mut self, tag: &str, timer: Option<Timer>) -> bool {
+        let Some(view) = self
+            .dynamic_site_tags
+            .get(tag)
+            .and_then(ViewCellWeakRef::upgrade)
+        else {
+            return false;
+        };
+        self.set_timer(&view, timer)
+    }
+
+    /// Restart all running timers now, see `Screen::now`.
+    pub(crate) fn restart_timers(&mut self) {
+        let now = self.now();
+        for scheduled in &mut self.timers {
+            scheduled.start = now;
+        }
+    }
+
+    /// When the next timer of a view fires, if any view has a timer.
+    /// Event loops should call `Screen::handle_timers` at that time.
+    pub fn timer_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(ScheduledTimer::deadline).min()
+    }
+
+    /// Call `MutView::on_tick` of views whose timers have fired.
+    pub fn handle_timers(&mut self) {
+        self.handle_timers_at(self.now());
+    }
+
+    fn handle_timers_at(&mut self, now: Instant) {
+        let mut idx = 0;
+        while let Some(scheduled) = self.timers.get(idx).copied() {
+            let deadline = scheduled.deadline();
+            if deadline > now {
+                idx += 1;
+                continue;
+            }
+            let Some(view) = self.site(scheduled.idx) else {
+                self.timers.swap_remove(idx);
+                continue;
+            };
+            let mut timer = match scheduled.timer {
+                Timer::Once(_) => None,
+                Timer::Interval(_) => Some(scheduled.timer),
+            };
+            let elapsed = now - scheduled.start;
+            let Ok(mut inner) = view.lock() else {
+                self.timers.swap_remove(idx);
+                continue;
+            };
+            inner.mark_dirty();
+            inner.view.on_tick(elapsed, &mut timer);
+            drop(inner);
+            let Some(timer) = timer else {
+                self.timers.swap_remove(idx);
+                continue;
+            };
+            // Keep the pace of interval timers, unless a whole interval was missed.
+            let start = match timer {
+                Timer::Interval(_)
+                    if timer == scheduled.timer && now < deadline + timer.duration() =>
+                {
+                    deadline
+                }
+                _ => now,
+            };
+            self.timers[idx] = ScheduledTimer {
+                idx: scheduled.idx,
+                timer,
+                start,
+            };
+            idx += 1;
+        }
+    }
+
+    fn run_command(&mut self, command: Command<'a, V>) -> EventResult {
+        match command {
+            Command::Action(action) => action.perform(self),
+            Command::Callback(callback) => {
+                // Fails if the callback is already running, i.e. it passed a key event that
+                // runs it again into the screen.
+                let Ok(mut callback) = callback.try_lock() else {
+                    return EventResult::Ignored;
+                };
+                callback(self);
+                self.mark_dirty();
+                EventResult::Handled
+            }
+        }
+    }
+
+    /// Pass an event into the screen.
+    /// Returns `EventResult::Ignored` if no view handled the event, so the caller may handle it
+    /// instead.
+    pub fn handle_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event),
+            Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+            Event::Paste(ref text) => {
+                self.dispatch(self.focused().as_ref(), &event, |view, timer| {
+                    view.on_paste(text, timer)
+                })
+            }
+            Event::Resize(..) => {
+                self.mark_dirty();
+                EventResult::Ignored
             }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    /// Look up keybindings, and dispatch the key event to the focused view if no keybinding
+    /// handled it.
+    /// The capture phase runs before keybindings are looked up, so views can stop keys before
+    /// they run keybindings (like Tab moving focus). Keys held back by keybindings go through the
+    /// capture phase only once, when they are pressed.
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> EventResult {
+        let event = Event::Key(key_event);
+        if key_event.kind == KeyEventKind::Release {
+            return self.dispatch(self.focused().as_ref(), &event, |view, timer| {
+                view.on_key_event(key_event, timer)
+            });
+        }
+        let now = self.now();
+        self.handle_key_timeout_at(now);
+        if self.capture(self.focused().as_ref(), &event).is_handled() {
+            return EventResult::Handled;
+        }
+        self.feed_key_event(key_event, now)
+    }
+
+    /// Feed a key event into `keymap`, and handle what it results in.
+    fn feed_key_event(&mut self, key_event: KeyEvent, now: Instant) -> EventResult {
+        let key_match = self.keymap.feed(KeyChord::from(key_event), now);
+        if !self.pending_key_events.is_empty() || matches!(key_match, KeyMatch::Pending) {
+            // The pending keys changed, which views may show (see `Screen::pending_keys`).
+            self.mark_dirty();
+        }
+        match key_match {
+            KeyMatch::Matched(command) => {
+                let mut key_events = mem::take(&mut self.pending_key_events);
+                key_events.push(key_event);
+                self.run_bound_keys(command, &key_events, now)
+            }
+            KeyMatch::Pending => {
+                self.pending_key_events.push(key_event);
+                EventResult::Handled
+            }
+            KeyMatch::Unmatched => self.dispatch_unbound_key(key_event, now),
+            KeyMatch::Interrupted(interrupted) => {
+                let mut key_events = mem::take(&mut self.pending_key_events);
+                key_events.push(key_event);
+                self.replay_interrupted(interrupted, &key_events, now)
+            }
+        }
+    }
+
+    /// Run the command of a key sequence of `keymap`.
+    /// If the command does not apply, the key events of the key sequence are handled as if they
+    /// were not bound.
+    fn run_bound_keys(
+        &mut self,
+        command: Command<'a, V>,
+        key_events: &[KeyEvent],
+        now: Instant,
+    ) -> EventResult {
+        if self.run_command(command).is_handled() {
+            return EventResult::Handled;
+        }
+        key_events
+            .iter()
+            .fold(EventResult::Ignored, |_, &key_event| {
+                self.dispatch_unbound_key(key_event, now)
+            })
+    }
+
+    /// Handle keys of `keymap` that did not complete a key sequence (see `Interrupted`).
+    /// `key_events` are the key events of `interrupted.keys`.
+    /// Returns the result of the last key event.
+    fn replay_interrupted(
+        &mut self,
+        interrupted: Interrupted<Command<'a, V>>,
+        key_events: &[KeyEvent],
+        now: Instant,
+    ) -> EventResult {
+        let (consumed, rest) = key_events.split_at(interrupted.consumed().min(key_events.len()));
+        let mut result = match interrupted.prefix {
+            Some((command, _)) => self.run_bound_keys(command, consumed, now),
+            None => consumed.iter().fold(EventResult::Ignored, |_, &key_event| {
+                self.dispatch_unbound_key(key_event, now)
+            }),
+        };
+        for &key_event in rest {
+            result = self.feed_key_event(key_event, now);
+        }
+        result
+    }
+
+    /// Dispatch a key event not bound in `keymap` to the focused view, then look it up in
+    /// `fallback_keymap` if the focused view ignored it.
+    fn dispatch_unbound_key(&mut self, key_event: KeyEvent, now: Instant) -> EventResult {
+        let event = Event::Key(key_event);
+        let result = self.dispatch_after_capture(self.focused().as_ref(), &event, |view, timer| {
+            view.on_key_event(key_event, timer)
+        });
+        if result.is_handled() {
+            if !self.fallback_keymap.pending().is_empty() {
+                self.fallback_keymap.clear_pending();
+                self.mark_dirty();
+            }
+            return result;
+        }
+        self.feed_fallback(KeyChord::from(key_event), now)
+    }
+
+    /// Feed a key chord the focused view ignored into `fallback_keymap`, and handle what it
+    /// results in.
+    fn feed_fallback(&mut self, chord: KeyChord, now: Instant) -> EventResult {
+        let had_pending = !self.fallback_keymap.pending().is_empty();
+        let key_match = self.fallback_keymap.feed(chord, now);
+        if had_pending || matches!(key_match, KeyMatch::Pending) {
+            // The pending keys changed, which views may show (see `Screen::pending_keys`).
+            self.mark_dirty();
+        }
+        match key_match {
+            KeyMatch::Matched(command) => self.run_command(command),
+            KeyMatch::Pending => EventResult::Handled,
+            KeyMatch::Unmatched => EventResult::Ignored,
+            KeyMatch::Interrupted(interrupted) => {
+                self.replay_fallback_interrupted(interrupted, now)
+            }
+        }
+    }
+
+    /// Handle keys of `fallback_keymap` that did not complete a key sequence (see
+    /// `Interrupted`). The focused view already ignored them, so unbound keys are dropped.
+    /// Returns the result of the last key.
+    fn replay_fallback_interrupted(
+        &mut self,
+        interrupted: Interrupted<Command<'a, V>>,
+        now: Instant,
+    ) -> EventResult {
+        let mut result = match interrupted.prefix.clone() {
+            Some((command, _)) => self.run_command(command),
+            None => EventResult::Ignored,
+        };
+        for &chord in interrupted.rest() {
+            result = self.feed_fallback(chord, now);
+        }
+        result
+    }
+
+    /// Path of views from the root view to the view of `target`.
+    /// If `target` is `None` or the target view is not in the tree of views, the path is the root
+    /// view and the views it wraps, down to the first view without exactly one child (so handlers
+    /// added to the root view with `ViewExt::on_capture` and `ViewExt::on_bubble` are all on it).
+    fn event_path(&self, target: Option<ViewCellId>) -> Vec<&dyn View> {
+        fn find_path<'s>(
+            view: &'s dyn View,
+            target: ViewCellId,
+            path: &mut Vec<&'s dyn View>,
+        ) -> bool {
+            path.push(view);
+            if view.view_cell_id() == Some(target) {
+                return true;
+            }
+            let mut found = false;
+            view.for_each_child(&mut |child| {
+                if !found {
+                    found = find_path(child, target, path);
+                }
+            });
+            if !found {
+                path.pop();
+            }
+            found
+        }
+        let mut path = Vec::new();
+        if let Some(target) = target {
+            if find_path(&self.root_view, target, &mut path) {
+                return path;
+            }
+        }
+        let mut view: &dyn View = &self.root_view;
+        loop {
+            path.push(view);
+            let mut children = Vec::new();
+            view.for_each_child(&mut |child| children.push(child));
+            match children[..] {
+                [child] => view = child,
+                _ => return path,
+            }
+        }
+    }
+
+    /// Dispatch an event DOM-style: capture phase from the root view down, then `at_target` on the
+    /// target view, then bubble phase back up to the root view.
+    /// Any view returning `Propagation::Stop` stops the event from going any further, in which
+    /// case the event counts as handled.
+    /// Marks the screen as changed if the event was handled.
+    fn dispatch(
+        &mut self,
+        target: Option<&ViewCell<'a>>,
+        event: &Event,
+        at_target: impl FnOnce(&mut (dyn MutView + Send + 'a), &mut Option<Timer>) -> EventResult,
+    ) -> EventResult {
+        match self.capture(target, event) {
+            EventResult::Handled => EventResult::Handled,
+            EventResult::Ignored => self.dispatch_after_capture(target, event, at_target),
+        }
+    }
+
+    /// Run the capture phase of `Screen::dispatch`.
+    /// Returns `EventResult::Handled` if a view stopped the event.
+    fn capture(&self, target: Option<&ViewCell<'a>>, event: &Event) -> EventResult {
+        let path = self.event_path(target.map(ViewCell::id));
+        for view in &path {
+            if view.capture_event(event) == Propagation::Stop {
+                self.mark_dirty();
+                return EventResult::Handled;
+            }
+        }
+        EventResult::Ignored
+    }
+
+    /// Run `Screen::dispatch` without the capture phase, for events that already went through it.
+    fn dispatch_after_capture(
+        &mut self,
+        target: Option<&ViewCell<'a>>,
+        event: &Event,
+        at_target: impl FnOnce(&mut (dyn MutView + Send + 'a), &mut Option<Timer>) -> EventResult,
+    ) -> EventResult {
+        let result = match target.map(|target| (target, target.lock())) {
+            Some((target, Ok(mut inner))) => {
+                let result = match self.site_index_of(&target.downgrade()) {
+                    Some(idx) => {
+                        self.with_site_timer(idx, |timer| at_target(inner.view.as_mut(), timer))
+                    }
+                    None => at_target(inner.view.as_mut(), &mut None),
+                };
+                if result.is_handled() {
+                    inner.mark_dirty();
+                }
+                result
+            }
+            Some((_, Err(_))) | None => EventResult::Ignored,
+        };
+        let path = self.event_path(target.map(ViewCell::id));
+        for view in path.iter().rev() {
+            if view.bubble_event(event) == Propagation::Stop {
+                self.mark_dirty();
+                return EventResult::Handled;
+            }
+        }
+        result
+    }
+
+    /// Index into `dynamic_sites` of the view rendered on a position by the last `Screen::render`.
+    /// If multiple views were rendered on the position, the most recently created one is chosen.
+    fn site_at(&self, position: Position) -> Option<(usize, ViewCell<'a>, Rect)> {
+        self.dynamic_sites
+            .iter()
+            .enumerate()
+            .rev()
+            .filter_map(|(idx, weak)| {
+                let view = weak.upgrade()?;
+                let area = view.last_area()?;
+                Some((idx, view, area))
+            })
+            .find(|(_, _, area)| area.contains(position))
+    }
+
+    /// Route a mouse event to the view under the mouse, in coordinates local to that view.
+    /// Pressing a mouse button on a focusable view focuses it, pressing it elsewhere (on a
+    /// non-focusable view, or outside of all views) leaves focus unchanged.
+    /// Views handling the event in capture and bubble phases receive it in screen coordinates.
+    /// If no view is under the mouse, the event only goes through the capture and bubble phases
+    /// of the root view and the views it wraps.
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> EventResult {
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        let Some((idx, view, area)) = self.site_at(position) else {
+            return self.dispatch(None, &Event::Mouse(mouse_event), |_, _| {
+                EventResult::Ignored
+            });
+        };
+        let mut result = EventResult::Ignored;
+        if let MouseEventKind::Down(_) = mouse_event.kind {
+            if self.site_is_focusable(idx) {
+                self.set_focus(Some(idx));
+                result = EventResult::Handled;
+            }
+        }
+        let local_event = MouseEvent {
+            column: position.x - area.x,
+            row: position.y - area.y,
+            ..mouse_event
+        };
+        match self.dispatch(Some(&view), &Event::Mouse(mouse_event), |view, timer| {
+            view.on_mouse_event(local_event, timer)
+        }) {
+            EventResult::Handled => EventResult::Handled,
+            EventResult::Ignored => result,
+        }
+    }
+}
+
+/// Built-in actions that can be bound to keys, see `Screen::bind_action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// `Screen::focus_next`.
+    FocusNext,
+    /// `Screen::focus_prev`.
+    FocusPrev,
+    /// `Screen::focus_first`.
+    FocusFirst,
+    /// `Screen::focus_last`.
+    FocusLast,
+    /// `Screen::focus_next_in_group`, does not apply if the focused view is not in a focus group.
+    FocusNextInGroup,
+    /// `Screen::focus_prev_in_group`, does not apply if the focused view is not in a focus group.
+    FocusPrevInGroup,
+    /// `Screen::focus_direction`.
+    FocusDirection(FocusDirection),
+    /// `Screen::blur`.
+    Blur,
+    /// `Screen::request_external_edit`.
+    /// Not bound by default, since a key sequence like `"C-x C-e"` (as in bash) would hold back
+    /// its first key from all views. Bind it with `Screen::bind_action`.
+    EditExternally,
+}
+
+impl Action {
+    /// Perform the action on a screen.
+    /// Returns `EventResult::Ignored` if the action does not apply.
+    fn perform<V: View>(self, screen: &mut Screen<V>) -> EventResult {
+        match self {
+            Self::FocusNext => screen.focus_next(),
+            Self::FocusPrev => screen.focus_prev(),
+            Self::FocusFirst => screen.focus_first(),
+            Self::FocusLast => screen.focus_last(),
+            Self::FocusNextInGroup | Self::FocusPrevInGroup if !screen.focused_is_in_group() => {
+                return EventResult::Ignored;
+            }
+            Self::FocusNextInGroup => screen.focus_next_in_group(),
+            Self::FocusPrevInGroup => screen.focus_prev_in_group(),
+            Self::FocusDirection(direction) => screen.focus_direction(direction),
+            Self::Blur => screen.blur(),
+            Self::EditExternally if !screen.request_external_edit() => {
+                return EventResult::Ignored;
+            }
+            Self::EditExternally => (),
+        }
+        EventResult::Handled
+    }
+}
+
+/// A callback bound to a key chord, see `Screen::bind`.
+type Callback<'a, V> = Arc<Mutex<dyn FnMut(&mut Screen<'a, V>) + Send + 'a>>;
+
+/// What a key chord is bound to in a `Screen`.
+enum Command<'a, V: View + 'a> {
+    Action(Action),
+    Callback(Callback<'a, V>),
+}
+
+impl<'a, V: View + 'a> Clone for Command<'a, V> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Action(action) => Self::Action(*action),
+            Self::Callback(callback) => Self::Callback(callback.clone()),
+        }
+    }
+}
+
+impl<'a, V: View + 'a> Debug for Command<'a, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Action(action) => f.debug_tuple("Action").field(action).finish(),
+            Self::Callback(_) => f.debug_tuple("Callback").finish_non_exhaustive(),
         }
-        None
     }
+}
 
-    /// Pass an event into the screen.
-    pub fn handle_event(&mut self, event: Event) {
-        match event {
-            Event::Key(KeyEvent {
-                code: KeyCode::BackTab,
-                modifiers: KeyModifiers::NONE,
-                kind: KeyEventKind::Press,
-                state: _,
-            }) => {
-                // TODO: self.focus_prev();
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Tab,
-                modifiers: KeyModifiers::NONE,
-                kind: KeyEventKind::Press,
-                state: _,
-            }) => {
-                self.focus_next();
-            }
-            Event::Key(key_event) => {
-                if let Some(focused_view) = self.focused() {
-                    focused_view
-                        .inner
-                        .lock()
-                        .unwrap()
-                        .view
-                        .on_key_event(key_event);
-                }
-            }
-            _ => (),
+/// Direction for spatial focus navigation, see `Screen::focus_direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FocusDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl FocusDirection {
+    /// How far `to` is from `from` in this direction, or `None` if `to` isn't in this direction.
+    /// Distance on the axis of movement is the gap between the two areas, distance on the other
+    /// axis is how far apart the two areas are (zero if they overlap), and counts double so views
+    /// roughly in line are preferred.
+    /// Ties are broken by how far apart the centers of the two areas are on the other axis.
+    fn distance(self, from: Rect, to: Rect) -> Option<(u32, u32)> {
+        fn gap(start0: u16, end0: u16, start1: u16, end1: u16) -> u32 {
+            u32::from(start1.saturating_sub(end0)) + u32::from(start0.saturating_sub(end1))
+        }
+        fn center_offset(start0: u16, end0: u16, start1: u16, end1: u16) -> u32 {
+            let center0 = u32::from(start0) + u32::from(end0);
+            let center1 = u32::from(start1) + u32::from(end1);
+            center0.abs_diff(center1)
         }
+        let (main, horizontal) = match self {
+            Self::Left if to.right() <= from.left() => (from.left() - to.right(), true),
+            Self::Right if to.left() >= from.right() => (to.left() - from.right(), true),
+            Self::Up if to.bottom() <= from.top() => (from.top() - to.bottom(), false),
+            Self::Down if to.top() >= from.bottom() => (to.top() - from.bottom(), false),
+            _ => return None,
+        };
+        let cross_axis = match horizontal {
+            true => (from.top(), from.bottom(), to.top(), to.bottom()),
+            false => (from.left(), from.right(), to.left(), to.right()),
+        };
+        let (start0, end0, start1, end1) = cross_axis;
+        Some((
+            u32::from(main) + 2 * gap(start0, end0, start1, end1),
+            center_offset(start0, end0, start1, end1),
+        ))
     }
 }
 
@@ -255,12 +1410,78 @@ pub trait View {
     fn preferred_size(&self) -> Option<Size> {
         None
     }
+
+    /// Call `f` on each of the child views of this view.
+    /// Views containing other views (like `Stack`) should implement this, so events can be
+    /// propagated through them (see `View::capture_event` and `View::bubble_event`).
+    #[allow(unused_variables)]
+    fn for_each_child<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {}
+
+    /// Called on each view on the path from the root view to the target view of an event, from
+    /// the root down, before the target view receives the event.
+    /// Key events are captured before keybindings of the screen are looked up.
+    /// Returning `Propagation::Stop` stops the event from going any further.
+    #[allow(unused_variables)]
+    fn capture_event(&self, event: &Event) -> Propagation {
+        Propagation::Continue
+    }
+
+    /// Called on each view on the path from the target view of an event to the root view, from
+    /// the target up, after the target view received the event.
+    /// Returning `Propagation::Stop` stops the event from going any further.
+    #[allow(unused_variables)]
+    fn bubble_event(&self, event: &Event) -> Propagation {
+        Propagation::Continue
+    }
+
+    /// Identity of this view if it is a `ViewCell`, for finding the path of an event.
+    fn view_cell_id(&self) -> Option<ViewCellId> {
+        None
+    }
+
+    /// Called with the theme of the screen on its root view when the screen is built, and when
+    /// the theme changes, see `Screen::set_theme`.
+    /// Views containing other views (like `Stack`) should pass it on to them. `ViewCell`s get the
+    /// theme from the screen directly, see `MutView::set_theme`.
+    #[allow(unused_variables)]
+    fn set_theme(&mut self, theme: &Theme) {}
+}
+
+/// Whether an event should continue propagating, see `View::capture_event` and
+/// `View::bubble_event`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Propagation {
+    #[default]
+    Continue,
+    Stop,
+}
+
+/// Whether an event was handled, returned by the event hooks of `MutView` and by
+/// `Screen::handle_event`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EventResult {
+    Handled,
+    #[default]
+    Ignored,
+}
+
+impl EventResult {
+    pub fn is_handled(self) -> bool {
+        self == Self::Handled
+    }
+
+    pub fn is_ignored(self) -> bool {
+        self == Self::Ignored
+    }
 }
 
 /// A mutable view.
 /// To be able to render a mutable view, wrap it in a `ViewCell`.
 #[allow(unused_variables)]
 pub trait MutView {
+    /// While focused, a view may place the terminal cursor with `Frame::set_cursor_position`,
+    /// e.g. at the caret of an input field, so input methods can show candidate windows there.
+    /// The terminal cursor is hidden if no view placed it.
     fn render(&self, frame: &mut Frame, area: Rect, is_focused: bool);
 
     /// Views like `Stack` will try to satisfy the wanted view size as much as possible.
@@ -275,16 +1496,139 @@ pub trait MutView {
         false
     }
 
-    fn on_focus(&mut self) {}
+    /// Called when this view gains focus.
+    /// Like in all event hooks, `timer` is the running timer of this view. Set it to start
+    /// another timer (restarting it if it was running), or to `None` to stop.
+    fn on_focus(&mut self, timer: &mut Option<Timer>) {}
+
+    /// Called when this view loses focus.
+    fn on_unfocus(&mut self, timer: &mut Option<Timer>) {}
+
+    /// Returns whether the key event was handled by this view.
+    /// `timer` is the running timer of this view, see `MutView::on_focus`.
+    fn on_key_event(&mut self, key_event: KeyEvent, timer: &mut Option<Timer>) -> EventResult {
+        EventResult::Ignored
+    }
+
+    /// Called for mouse events on the area this view was last rendered in.
+    /// `column` and `row` of the mouse event are relative to the top-left corner of that area.
+    /// Returns whether the mouse event was handled by this view.
+    fn on_mouse_event(
+        &mut self,
+        mouse_event: MouseEvent,
+        timer: &mut Option<Timer>,
+    ) -> EventResult {
+        EventResult::Ignored
+    }
+
+    /// Called when text is pasted (with bracketed paste enabled, see `setup_terminal`) while this
+    /// view is focused.
+    /// Returns whether the paste was handled by this view.
+    fn on_paste(&mut self, text: &str, timer: &mut Option<Timer>) -> EventResult {
+        EventResult::Ignored
+    }
+
+    /// Called when the timer of this view fires, with the time elapsed since the timer was
+    /// started or last fired.
+    /// `timer` is the timer that stays running afterwards: the same timer for `Timer::Interval`,
+    /// `None` for `Timer::Once`. Set it to start another timer, or to `None` to stop.
+    /// Timers are started with `ViewCell::timer` or `Screen::set_timer`, or from the view itself
+    /// in any of its event hooks.
+    fn on_tick(&mut self, elapsed: Duration, timer: &mut Option<Timer>) {}
+
+    /// Style of the terminal cursor while this view is focused, see `MutView::render`.
+    fn cursor_style(&self) -> CursorStyle {
+        CursorStyle::Default
+    }
+
+    /// Called with the theme of the screen when this view is added to it, and when the theme
+    /// changes, see `Screen::set_theme`.
+    fn set_theme(&mut self, theme: &Theme) {}
+
+    /// The text content of this view, if it can be edited in an external editor (see
+    /// `Action::EditExternally`).
+    fn input_field_content_mut(&mut self) -> Option<&mut InputFieldContent> {
+        None
+    }
+}
+
+/// Style of the terminal cursor, see `MutView::cursor_style`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CursorStyle {
+    /// The cursor style configured by the user in the terminal.
+    #[default]
+    Default,
+    BlinkingBlock,
+    SteadyBlock,
+    BlinkingUnderline,
+    SteadyUnderline,
+    BlinkingBar,
+    SteadyBar,
+}
+
+impl From<CursorStyle> for SetCursorStyle {
+    fn from(cursor_style: CursorStyle) -> Self {
+        match cursor_style {
+            CursorStyle::Default => Self::DefaultUserShape,
+            CursorStyle::BlinkingBlock => Self::BlinkingBlock,
+            CursorStyle::SteadyBlock => Self::SteadyBlock,
+            CursorStyle::BlinkingUnderline => Self::BlinkingUnderScore,
+            CursorStyle::SteadyUnderline => Self::SteadyUnderScore,
+            CursorStyle::BlinkingBar => Self::BlinkingBar,
+            CursorStyle::SteadyBar => Self::SteadyBar,
+        }
+    }
+}
+
+/// A timer of a view, calls `MutView::on_tick` of the view when it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timer {
+    /// Fires once after the duration.
+    Once(Duration),
+    /// Fires repeatedly with the duration in between, at least `Timer::MIN_INTERVAL`.
+    Interval(Duration),
+}
+
+impl Timer {
+    /// Shortest duration between firings of `Timer::Interval`, shorter ones are raised to it so
+    /// the event loop does not spin.
+    pub const MIN_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// The duration until the timer fires (again).
+    pub fn duration(self) -> Duration {
+        match self {
+            Self::Once(duration) => duration,
+            Self::Interval(duration) => duration.max(Self::MIN_INTERVAL),
+        }
+    }
+}
 
-    fn on_unfocus(&mut self) {}
+/// A running timer of the view at `idx` (index into `Screen::dynamic_sites`).
+#[derive(Debug, Clone, Copy)]
+struct ScheduledTimer {
+    idx: usize,
+    timer: Timer,
+    /// When the timer was started or last fired.
+    start: Instant,
+}
 
-    fn on_key_event(&mut self, key_event: KeyEvent) {}
+impl ScheduledTimer {
+    fn deadline(&self) -> Instant {
+        self.start + self.timer.duration()
+    }
 }
 
 /// Wrap a `MutView` into a `View` through internal mutability.
 /// Also erases its type.
 /// Can be created by calling `view_cell` on `Screen` or `ScreenBuilder`.
+///
+/// If a method of the wrapped view panics, the view is poisoned: it is no longer rendered,
+/// focused or passed events, and `Screen::render`, `ViewCell::inspect` and `ExternalEdit::finish`
+/// fail with `Error::PoisonedLock`.
+/// Other methods of `Screen` (e.g. `Screen::handle_event`, `Screen::set_focus`,
+/// `Screen::handle_timers` and `Screen::process_messages`) skip poisoned views, as if they ignored
+/// what was passed to them, but mark the screen as changed, so that the next `Screen::render`
+/// (which event loops do right away) reports the poisoning.
 #[derive(Debug, Clone, From)]
 pub struct ViewCell<'a> {
     inner: Arc<Mutex<ViewCellInner<'a>>>,
@@ -292,9 +1636,18 @@ pub struct ViewCell<'a> {
 
 impl<'a> ViewCell<'a> {
     /// Internal function for creating a new `ViewCell`.
-    fn new(is_focused: bool, view: impl MutView + Send + 'a) -> Self {
+    fn new(
+        is_focused: bool,
+        render_state: Arc<RenderState>,
+        view: impl MutView + Send + 'a,
+    ) -> Self {
         let inner = ViewCellInner {
             is_focused,
+            render_state,
+            tab_index: 0,
+            focus_group: None,
+            last_area: None,
+            timer: None,
             view: Box::new(view),
         };
         Self {
@@ -302,6 +1655,61 @@ impl<'a> ViewCell<'a> {
         }
     }
 
+    /// Set the tab index of this view.
+    /// Tab order is ascending in tab index, views of the same tab index are ordered in the order
+    /// they were created. Views default to a tab index of `0`.
+    /// If changed after the screen was built, call `Screen::refresh_tab_order` afterwards.
+    pub fn tab_index(self, tab_index: i32) -> Self {
+        if let Ok(mut inner) = self.lock() {
+            inner.tab_index = tab_index;
+        }
+        self
+    }
+
+    /// Put this view into a named focus group.
+    /// A focus group is a single stop in tab order, arrow keys move focus within the group
+    /// (roving focus, like in a toolbar). Because of this, focused views inside a focus group do
+    /// not receive arrow keys without modifiers.
+    /// If changed after the screen was built, call `Screen::refresh_tab_order` afterwards.
+    pub fn focus_group(self, group: impl Into<Cow<'a, str>>) -> Self {
+        if let Ok(mut inner) = self.lock() {
+            inner.focus_group = Some(group.into());
+        }
+        self
+    }
+
+    /// Start a timer for this view when the screen is built, see `MutView::on_tick`.
+    /// For a screen that was already built, use `Screen::set_timer` instead.
+    pub fn timer(self, timer: Timer) -> Self {
+        if let Ok(mut inner) = self.lock() {
+            inner.timer = Some(timer);
+        }
+        self
+    }
+
+    /// Identity of this view.
+    pub fn id(&self) -> ViewCellId {
+        ViewCellId(Arc::as_ptr(&self.inner) as *const () as usize)
+    }
+
+    /// The area this view was rendered in by the last `Screen::render`.
+    /// Returns `None` if it was not rendered then, or is poisoned.
+    pub fn last_area(&self) -> Option<Rect> {
+        let inner = self.lock().ok()?;
+        let (render, area) = inner.last_area?;
+        (render == inner.render_state.renders.load(Ordering::Relaxed)).then_some(area)
+    }
+
+    /// Lock the wrapped view, failing if it is poisoned.
+    /// A poisoned view marks the screen as changed, so that the next `Screen::render` reports it,
+    /// even if the caller skips the view.
+    fn lock(&self) -> Result<MutexGuard<'_, ViewCellInner<'a>>> {
+        self.inner.lock().map_err(|error| {
+            error.get_ref().mark_dirty();
+            Error::PoisonedLock
+        })
+    }
+
     /// Downgrade to a weak reference.
     fn downgrade(&self) -> ViewCellWeakRef<'a> {
         Arc::downgrade(&self.inner).into()
@@ -309,12 +1717,14 @@ impl<'a> ViewCell<'a> {
 
     /// Downcast the wrapped `MutView` into a value of concrete type.
     /// Because `ViewCell` erases the type of the wrapped view, such downcasting is `unsafe`.
+    /// Marks the screen as changed (see `Screen::needs_render`).
+    /// Fails with `Error::PoisonedLock` if the view is poisoned.
     ///
     /// FIXME: make it safe.
     ///
     /// # Safety
     /// `V` must be of the correct type that the value was initialized with.
-    pub unsafe fn inspect<T, MV: MutView + 'a>(&self, f: impl FnOnce(&mut MV) -> T) -> T {
+    pub unsafe fn inspect<T, MV: MutView + 'a>(&self, f: impl FnOnce(&mut MV) -> T) -> Result<T> {
         trait RawPtr {
             fn raw_ptr(&mut self) -> *mut ();
         }
@@ -323,33 +1733,109 @@ impl<'a> ViewCell<'a> {
                 self as *mut V as *mut ()
             }
         }
-        let mut borrow_mut = self.inner.lock().unwrap();
+        let mut borrow_mut = self.lock()?;
+        borrow_mut.mark_dirty();
         let view: &mut MV = unsafe { &mut *(borrow_mut.view.as_mut().raw_ptr() as *mut _) };
-        f(view)
+        Ok(f(view))
     }
 }
 
 impl View for ViewCell<'_> {
     fn render(&self, frame: &mut Frame, area: Rect) {
-        let inner = self.inner.lock().unwrap();
+        // Only reached for a poisoned view when rendered outside of `Screen::render`, which fails
+        // before drawing anything.
+        let Ok(mut inner) = self.lock() else {
+            return;
+        };
+        let render = inner.render_state.renders.load(Ordering::Relaxed);
+        inner.last_area = Some((render, area));
         inner.view.render(frame, area, inner.is_focused);
     }
 
     fn preferred_size(&self) -> Option<Size> {
-        self.inner.lock().unwrap().view.preferred_size()
+        self.lock().ok()?.view.preferred_size()
+    }
+
+    fn view_cell_id(&self) -> Option<ViewCellId> {
+        Some(self.id())
+    }
+}
+
+/// Text of a view to be edited in an external editor, see `Screen::take_external_edit`.
+#[derive(Debug)]
+pub struct ExternalEdit<'a> {
+    view: ViewCell<'a>,
+    text: String,
+}
+
+impl ExternalEdit<'_> {
+    /// The text before editing.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text of the view with the edited text, moving the caret to its end.
+    /// Fails with `Error::PoisonedLock` if the view is poisoned.
+    pub fn finish(self, text: String) -> Result<()> {
+        let mut inner = self.view.lock()?;
+        inner.mark_dirty();
+        if let Some(content) = inner.view.input_field_content_mut() {
+            content.set_text(text);
+            content.cursor_to_end();
+        }
+        Ok(())
+    }
+}
+
+/// Identity of a `ViewCell`, two `ViewCell`s have the same ID if they refer to the same view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ViewCellId(usize);
+
+/// Whether a screen needs to be rendered, and how often it was, shared between the screen and its
+/// `ViewCell`s.
+#[derive(Debug, Default)]
+struct RenderState {
+    /// If anything changed since the last render.
+    dirty: AtomicBool,
+    /// Number of renders so far.
+    renders: AtomicU64,
+}
+
+impl RenderState {
+    fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Relaxed);
     }
 }
 
 struct ViewCellInner<'a> {
     is_focused: bool,
+    /// `Screen::render_state` of the screen this view is in.
+    render_state: Arc<RenderState>,
+    tab_index: i32,
+    focus_group: Option<Cow<'a, str>>,
+    /// The area this view was last rendered in, with the number of renders of the screen then.
+    /// Outdated if the screen was rendered again since, without this view.
+    last_area: Option<(u64, Rect)>,
+    /// Timer to start when the screen is built, see `ViewCell::timer`.
+    timer: Option<Timer>,
     /// FIXME: Remove this `Box` for one less indirection.
     view: Box<dyn MutView + Send + 'a>,
 }
 
+impl ViewCellInner<'_> {
+    fn mark_dirty(&self) {
+        self.render_state.mark_dirty();
+    }
+}
+
 impl Debug for ViewCellInner<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("ViewCellInner")
             .field("is_focused", &self.is_focused)
+            .field("tab_index", &self.tab_index)
+            .field("focus_group", &self.focus_group)
+            .field("last_area", &self.last_area)
+            .field("timer", &self.timer)
             .finish_non_exhaustive()
     }
 }
@@ -409,6 +1895,14 @@ impl<V: View> View for SizedView<V> {
     fn preferred_size(&self) -> Option<Size> {
         Some(self.preferred_size)
     }
+
+    fn for_each_child<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.inner);
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.inner.set_theme(theme);
+    }
 }
 
 impl<V: View> SizedView<V> {
@@ -420,24 +1914,113 @@ impl<V: View> SizedView<V> {
     }
 }
 
+/// Wraps an inner view and handles events propagating through it, see `ViewExt::on_capture` and
+/// `ViewExt::on_bubble`.
+/// Handlers only see the event. To act on it, e.g. update views or move focus, move a
+/// `ScreenHandle` (see `Screen::handle`) into the handler and post a message or update through it.
+#[derive(Clone, Copy)]
+pub struct EventHandler<V: View, F: Fn(&Event) -> Propagation> {
+    phase: EventPhase,
+    handler: F,
+    inner: V,
+}
+
+/// The phase of event propagation an `EventHandler` handles events in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventPhase {
+    /// From the root view down to the target view.
+    Capture,
+    /// From the target view up to the root view.
+    Bubble,
+}
+
+impl<V: View, F: Fn(&Event) -> Propagation> EventHandler<V, F> {
+    pub const fn new(phase: EventPhase, handler: F, inner: V) -> Self {
+        Self {
+            phase,
+            handler,
+            inner,
+        }
+    }
+}
+
+impl<V: View, F: Fn(&Event) -> Propagation> View for EventHandler<V, F> {
+    fn render(&self, frame: &mut Frame, area: Rect) {
+        self.inner.render(frame, area);
+    }
+
+    fn preferred_size(&self) -> Option<Size> {
+        self.inner.preferred_size()
+    }
+
+    fn for_each_child<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        f(&self.inner);
+    }
+
+    fn capture_event(&self, event: &Event) -> Propagation {
+        match self.phase {
+            EventPhase::Capture => (self.handler)(event),
+            EventPhase::Bubble => Propagation::Continue,
+        }
+    }
+
+    fn bubble_event(&self, event: &Event) -> Propagation {
+        match self.phase {
+            EventPhase::Capture => Propagation::Continue,
+            EventPhase::Bubble => (self.handler)(event),
+        }
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.inner.set_theme(theme);
+    }
+}
+
+impl<V: View + Debug, F: Fn(&Event) -> Propagation> Debug for EventHandler<V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventHandler")
+            .field("phase", &self.phase)
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
 pub trait ViewExt: View + Sized {
     fn prefers_size(self, preferred_size: impl Into<Size>) -> SizedView<Self> {
         SizedView::new(preferred_size.into(), self)
     }
+
+    /// Handle events targeting views inside this view, before the target view does.
+    fn on_capture<F: Fn(&Event) -> Propagation>(self, handler: F) -> EventHandler<Self, F> {
+        EventHandler::new(EventPhase::Capture, handler, self)
+    }
+
+    /// Handle events targeting views inside this view, after the target view did.
+    fn on_bubble<F: Fn(&Event) -> Propagation>(self, handler: F) -> EventHandler<Self, F> {
+        EventHandler::new(EventPhase::Bubble, handler, self)
+    }
 }
 
 impl<V: View + Sized> ViewExt for V {}
 
 /// An immutable view that displays some text.
+/// The text is styled with `Theme::text` and the borders of its block with `Theme::muted`, styles
+/// set on the paragraph and its block are patched over them.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Paragraph<'a> {
     widget: widgets::Paragraph<'a>,
+    /// Kept apart from `widget` for styling its borders with the theme, see `View::render`.
+    block: Option<Block<'a>>,
+    /// Theme of the screen, see `View::set_theme`.
+    theme: Theme,
 }
 
 impl<'a> Paragraph<'a> {
     pub fn new(text: impl Into<Text<'a>>) -> Self {
         Self {
             widget: widgets::Paragraph::new(text),
+            block: None,
+            theme: Theme::default(),
         }
     }
 
@@ -471,7 +2054,7 @@ impl<'a> Paragraph<'a> {
     }
 
     pub fn block(mut self, block: Block<'a>) -> Self {
-        self.widget = self.widget.block(block);
+        self.block = Some(block);
         self
     }
 
@@ -479,11 +2062,47 @@ impl<'a> Paragraph<'a> {
         self.widget = self.widget.alignment(alignment);
         self
     }
+
+    /// Scroll the text by `(y, x)`.
+    pub fn scroll(mut self, offset: (u16, u16)) -> Self {
+        self.widget = self.widget.scroll(offset);
+        self
+    }
 }
 
 impl<'a> View for Paragraph<'a> {
     fn render(&self, frame: &mut Frame, area: Rect) {
-        frame.render_widget(&self.widget, area);
+        let area = area.intersection(frame.area());
+        frame.buffer_mut().set_style(area, self.theme.text);
+        let inner_area = match &self.block {
+            Some(block) => {
+                let inner_area = block.inner(area);
+                style_borders(frame.buffer_mut(), area, inner_area, self.theme.muted);
+                // Like `widgets::Paragraph` does, style the block with the paragraph as well.
+                frame.buffer_mut().set_style(area, self.get_style());
+                frame.render_widget(block, area);
+                inner_area
+            }
+            None => area,
+        };
+        frame.render_widget(&self.widget, inner_area);
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
+}
+
+/// Style the cells of `area` outside of `inner_area`, i.e. the borders of a block.
+/// Done before rendering the block, so styles of the block take precedence.
+fn style_borders(buffer: &mut Buffer, area: Rect, inner_area: Rect, style: Style) {
+    let area = area.intersection(buffer.area);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            if !inner_area.contains(Position::new(x, y)) {
+                buffer[(x, y)].set_style(style);
+            }
+        }
     }
 }
 
@@ -537,24 +2156,30 @@ impl<Vs: ViewTuple> View for Stack<Vs> {
         self.children.for_each_render(frame, |i, preferred_size| {
             let mut area = chunks[i];
             match (self.direction, preferred_size) {
-                (Direction::Horizontal, Some(preferrde_size)) => {
-                    if preferrde_size.height != 0 {
-                        area.height = preferrde_size.height.min(area.height);
-                    }
+                (Direction::Horizontal, Some(preferred_size)) if preferred_size.height != 0 => {
+                    area.height = preferred_size.height.min(area.height);
                 }
-                (Direction::Vertical, Some(preferred_size)) => {
-                    if preferred_size.width != 0 {
-                        area.width = preferred_size.width.min(area.width);
-                    }
+                (Direction::Vertical, Some(preferred_size)) if preferred_size.width != 0 => {
+                    area.width = preferred_size.width.min(area.width);
                 }
                 _ => (),
             };
             area
         })
     }
+
+    fn for_each_child<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+        self.children.for_each_view(f);
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.children
+            .for_each_view_mut(&mut |child| child.set_theme(theme));
+    }
 }
 
-/// FIXME: make it multi-line.
+/// An editable text field.
+/// Fields of more than one row wrap the text, single rows scroll horizontally instead.
 #[derive(Debug, Clone, Hash)]
 pub struct InputField<'a> {
     placeholder: Cow<'a, str>,
@@ -563,8 +2188,12 @@ pub struct InputField<'a> {
     style_unfocused: Style,
     style_placeholder: Style,
     style_selection: Style,
+    style_caret: Style,
     block_focused: Block<'a>,
     block_unfocused: Block<'a>,
+    cursor_style: CursorStyle,
+    /// Theme of the screen, see `MutView::set_theme`.
+    theme: Theme,
 }
 
 impl<'a> Default for InputField<'a> {
@@ -572,12 +2201,15 @@ impl<'a> Default for InputField<'a> {
         Self {
             placeholder: Cow::default(),
             content: InputFieldContent::default(),
-            style_focused: Style::default().fg(Color::White),
-            style_unfocused: Style::default().fg(Color::White),
-            style_placeholder: Style::new().fg(Color::DarkGray),
-            style_selection: Style::new().bg(Color::LightBlue).fg(Color::Black),
+            style_focused: Style::new(),
+            style_unfocused: Style::new(),
+            style_placeholder: Style::new(),
+            style_selection: Style::new(),
+            style_caret: Style::new(),
             block_focused: Block::default(),
             block_unfocused: Block::default(),
+            cursor_style: CursorStyle::SteadyBar,
+            theme: Theme::default(),
         }
     }
 }
@@ -603,36 +2235,57 @@ impl<'a> InputField<'a> {
         self
     }
 
+    /// Style of the text while focused, patched over `Theme::text`.
     pub fn style_focused(mut self, style_focused: Style) -> Self {
         self.style_focused = style_focused;
         self
     }
 
+    /// Style of the text while unfocused, patched over `Theme::text`.
     pub fn style_unfocused(mut self, style_unfocused: Style) -> Self {
         self.style_unfocused = style_unfocused;
         self
     }
 
+    /// Block around the input field while focused, its borders are styled with
+    /// `Theme::focus_border` unless the block styles them.
     pub fn block_focused(mut self, block_focused: Block<'a>) -> Self {
         self.block_focused = block_focused;
         self
     }
 
+    /// Block around the input field while unfocused, its borders are styled with `Theme::muted`
+    /// unless the block styles them.
     pub fn block_unfocused(mut self, block_unfocused: Block<'a>) -> Self {
         self.block_unfocused = block_unfocused;
         self
     }
 
+    /// Patched over `Theme::muted`.
     pub fn style_placeholder(mut self, style_placeholder: Style) -> Self {
         self.style_placeholder = style_placeholder;
         self
     }
 
+    /// Patched over `Theme::selection`.
     pub fn style_selection(mut self, style_selection: Style) -> Self {
         self.style_selection = style_selection;
         self
     }
 
+    /// Style of the cell under the terminal cursor while focused, patched over `Theme::caret`.
+    pub fn style_caret(mut self, style_caret: Style) -> Self {
+        self.style_caret = style_caret;
+        self
+    }
+
+    /// Style of the terminal cursor while this input field is focused.
+    /// Defaults to `CursorStyle::SteadyBar`.
+    pub fn cursor_style(mut self, cursor_style: CursorStyle) -> Self {
+        self.cursor_style = cursor_style;
+        self
+    }
+
     pub fn content(&self) -> &InputFieldContent {
         &self.content
     }
@@ -641,60 +2294,83 @@ impl<'a> InputField<'a> {
         &mut self.content
     }
 
-    fn render_paragraph(&'a self, is_focused: bool) -> Paragraph<'a> {
+    /// Byte ranges of the rows the text is shown in, without line breaks.
+    /// With `width`, lines are wrapped after the last space before a word that does not fit, or
+    /// within words longer than a row. A line that exactly fills its last row is followed by an
+    /// empty row, where the caret goes at the end of the line.
+    fn rows(&self, width: Option<u16>) -> Vec<ops::Range<usize>> {
         let text = self.content.text();
-        if text.is_empty() {
-            return self.render_placeholder_paragraph(is_focused);
-        }
-        if !is_focused {
-            return Paragraph::new(text).style(self.style_unfocused);
-        }
-        match self.content.cursor() {
-            Cursor::Caret(caret) => {
-                if self.content.caret_is_at_end() {
-                    return Paragraph::new(Line::from(vec![
-                        Span::styled(text, self.style_focused),
-                        Span::styled(" ", self.caret_style()),
-                    ]));
+        let width = width.map_or(usize::MAX, |width| usize::from(width.max(1)));
+        let mut rows = Vec::new();
+        let mut line_start = 0;
+        for line in text.split('\n') {
+            let mut start = line_start;
+            let mut row_width = 0;
+            // Where the row can be broken, after the last space in it.
+            let mut break_at = None;
+            for (i, char) in line.char_indices() {
+                let i = line_start + i;
+                let char_width = Span::raw(&text[i..i + char.len_utf8()]).width();
+                while row_width + char_width > width && i > start {
+                    let end = break_at.take().unwrap_or(i);
+                    rows.push(start..end);
+                    row_width = Span::raw(&text[end..i]).width();
+                    start = end;
                 }
-                let caret_next = input_field::next_index_in_str(text, caret);
-                Paragraph::new(Line::from(vec![
-                    Span::styled(&text[0..caret], self.style_focused),
-                    Span::styled(&text[caret..caret_next], self.caret_style()),
-                    Span::styled(&text[caret_next..], self.style_focused),
-                ]))
-            }
-            Cursor::Selection(range) => Paragraph::new(Line::from(vec![
-                Span::styled(&text[0..range.start], self.style_focused),
-                Span::styled(&text[range], self.style_selection),
-                Span::styled(&text[range.end..], self.style_focused),
-            ])),
-        }
-    }
-
-    fn render_placeholder_paragraph(&'a self, is_focused: bool) -> Paragraph<'a> {
-        if is_focused {
-            let placeholder: &'a str = match &self.placeholder {
-                Cow::Borrowed(s) => s,
-                Cow::Owned(s) => s.as_str(),
-            };
-            let placeholder_caret_style = self.caret_style().patch(self.style_placeholder);
-            let head: &'a str = placeholder.get(..1).unwrap_or("");
-            let tail: &'a str = placeholder.get(1..).unwrap_or("");
-            Paragraph::new(Line::from(vec![
-                Span::styled(head, placeholder_caret_style),
-                Span::styled(tail, self.style_placeholder),
-            ]))
-        } else {
-            Paragraph::new(Line::from(vec![Span::styled(
-                &self.placeholder[..],
-                self.style_placeholder,
-            )]))
+                row_width += char_width;
+                if char == ' ' {
+                    break_at = Some(i + 1);
+                }
+            }
+            line_start += line.len();
+            rows.push(start..line_start);
+            if row_width >= width {
+                rows.push(line_start..line_start);
+            }
+            line_start += 1;
         }
+        rows
+    }
+
+    /// The text in `rows` (see `InputField::rows`), with the selection styled.
+    fn lines(&self, rows: &[ops::Range<usize>], style: Style, is_focused: bool) -> Vec<Line<'_>> {
+        let text = self.content.text();
+        let selection = match self.content.cursor() {
+            Cursor::Selection(range) if is_focused => range,
+            _ => {
+                return rows
+                    .iter()
+                    .map(|row| Line::raw(&text[row.clone()]))
+                    .collect()
+            }
+        };
+        let style_selection = style
+            .patch(self.theme.selection)
+            .patch(self.style_selection);
+        rows.iter()
+            .map(|row| {
+                let start = selection.start.clamp(row.start, row.end);
+                let end = selection.end.clamp(row.start, row.end);
+                Line::from(vec![
+                    Span::raw(&text[row.start..start]),
+                    Span::styled(&text[start..end], style_selection),
+                    Span::raw(&text[end..row.end]),
+                ])
+            })
+            .collect()
     }
 
-    fn caret_style(&self) -> Style {
-        Style::new().bg(Color::White).fg(Color::Black)
+    /// Row and column (in terminal cells) of the caret in `rows` (see `InputField::rows`).
+    fn caret_position(&self, rows: &[ops::Range<usize>]) -> (u16, u16) {
+        let caret = self.content.caret();
+        let row = rows.iter().rposition(|row| row.start <= caret).unwrap_or(0);
+        let column = rows.get(row).map_or(0, |row| {
+            Span::raw(&self.content.text()[row.start..caret]).width()
+        });
+        (
+            u16::try_from(row).unwrap_or(u16::MAX),
+            u16::try_from(column).unwrap_or(u16::MAX),
+        )
     }
 }
 
@@ -705,18 +2381,70 @@ impl<'a> MutView for InputField<'a> {
         } else {
             self.block_unfocused.clone()
         };
-        let paragraph = self
-            .render_paragraph(is_focused)
-            .block(block)
-            .wrap(Wrap { trim: false });
-        paragraph.render(frame, area);
+        let inner_area = block.inner(area);
+        let border_style = if is_focused {
+            self.theme.focus_border
+        } else {
+            self.theme.muted
+        };
+        style_borders(frame.buffer_mut(), area, inner_area, border_style);
+        let area = area.intersection(frame.area());
+        frame.render_widget(block, area);
+        // Fields of more than one row wrap the text, single rows scroll horizontally instead.
+        // Either way, scroll so the caret stays visible.
+        let wrap = inner_area.height > 1;
+        let rows = self.rows(wrap.then_some(inner_area.width));
+        let (row, column) = self.caret_position(&rows);
+        let scroll = (
+            row.saturating_sub(inner_area.height.saturating_sub(1)),
+            match wrap {
+                true => 0,
+                false => column.saturating_sub(inner_area.width.saturating_sub(1)),
+            },
+        );
+        let mut paragraph = if self.content.text().is_empty() {
+            let style = self.theme.muted.patch(self.style_placeholder);
+            Paragraph::new(&self.placeholder[..])
+                .style(style)
+                .wrap(Wrap { trim: false })
+        } else {
+            let style = self.theme.text.patch(match is_focused {
+                true => self.style_focused,
+                false => self.style_unfocused,
+            });
+            Paragraph::new(self.lines(&rows, style, is_focused)).style(style)
+        };
+        paragraph.set_theme(&self.theme);
+        paragraph.scroll(scroll).render(frame, inner_area);
+        if is_focused && !self.content.is_in_selection_mode() && !inner_area.is_empty() {
+            let caret = Position::new(
+                inner_area.x + (column - scroll.1),
+                inner_area.y + (row - scroll.0),
+            );
+            if let Some(cell) = frame.buffer_mut().cell_mut(caret) {
+                cell.set_style(self.theme.caret.patch(self.style_caret));
+            }
+            frame.set_cursor_position(caret);
+        }
+    }
+
+    fn cursor_style(&self) -> CursorStyle {
+        self.cursor_style
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
+
+    fn input_field_content_mut(&mut self) -> Option<&mut InputFieldContent> {
+        Some(&mut self.content)
     }
 
     fn is_focusable(&self) -> bool {
         true
     }
 
-    fn on_key_event(&mut self, key_event: KeyEvent) {
+    fn on_key_event(&mut self, key_event: KeyEvent, _timer: &mut Option<Timer>) -> EventResult {
         const CONTROL_SHIFT: KeyModifiers = match KeyModifiers::from_bits(
             KeyModifiers::CONTROL.bits() | KeyModifiers::SHIFT.bits(),
         ) {
@@ -759,7 +2487,909 @@ impl<'a> MutView for InputField<'a> {
                     self.content.insert(char);
                 }
             }
-            _ => (),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+
+    fn on_paste(&mut self, text: &str, _timer: &mut Option<Timer>) -> EventResult {
+        // Terminals may send line breaks as `\r` in pasted text.
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        self.content.batch_insert(&text);
+        EventResult::Handled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::atomic::AtomicUsize,
+    };
+
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+    use crate::testing::{
+        fixtures::{self, Log, Probe, Ticker},
+        TestScreen,
+    };
+
+    /// A screen with one focused `InputField`.
+    fn input_screen<'a>() -> (Screen<'a, ViewCell<'a>>, ViewCell<'a>) {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(InputField::default());
+        builder.initial_focus(&input);
+        (builder.finish(input.clone()), input)
+    }
+
+    fn text(input: &ViewCell) -> String {
+        unsafe { input.inspect(|field: &mut InputField| field.content().text().to_owned()) }
+            .unwrap()
+    }
+
+    fn focused<V: View>(screen: &Screen<V>) -> Option<ViewCellId> {
+        screen.focused().as_ref().map(ViewCell::id)
+    }
+
+    #[test]
+    fn focus_prev_first_last() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", &log));
+        let b = builder.view_cell(Probe::new("b", &log).unfocusable());
+        let c = builder.view_cell(Probe::new("c", &log));
+        let mut screen = builder.finish(Stack::vertical((a.clone(), b, c.clone())));
+        assert_eq!(focused(&screen), None);
+        screen.focus_prev();
+        assert_eq!(focused(&screen), Some(c.id()));
+        screen.focus_prev();
+        assert_eq!(focused(&screen), Some(a.id()));
+        screen.focus_prev();
+        assert_eq!(focused(&screen), Some(c.id()));
+        screen.focus_first();
+        assert_eq!(focused(&screen), Some(a.id()));
+        screen.focus_last();
+        assert_eq!(focused(&screen), Some(c.id()));
+        screen.focus_next();
+        assert_eq!(focused(&screen), Some(a.id()));
+        assert_eq!(
+            fixtures::take(&log),
+            [
+                "c focus",
+                "c unfocus",
+                "a focus",
+                "a unfocus",
+                "c focus",
+                "c unfocus",
+                "a focus",
+                "a unfocus",
+                "c focus",
+                "c unfocus",
+                "a focus",
+            ],
+        );
+    }
+
+    #[test]
+    fn focus_skips_dropped_and_follows_added_views() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", &log));
+        let b = builder.view_cell(Probe::new("b", &log));
+        builder.initial_focus(&a);
+        let mut screen = builder.finish(Empty);
+        drop(b);
+        screen.focus_next();
+        assert_eq!(focused(&screen), Some(a.id()));
+        let c = screen.view_cell(Probe::new("c", &log));
+        screen.focus_next();
+        assert_eq!(focused(&screen), Some(c.id()));
+        screen.focus_first();
+        screen.focus_prev();
+        assert_eq!(focused(&screen), Some(c.id()));
+        drop(c);
+        assert_eq!(focused(&screen), None);
+        screen.focus_next();
+        assert_eq!(focused(&screen), Some(a.id()));
+    }
+
+    #[test]
+    fn initial_focus_is_focused_on_finish() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let _a = builder.view_cell(Probe::new("a", &log));
+        let b = builder.view_cell(Probe::new("b", &log));
+        builder.initial_focus(&b);
+        let screen = builder.finish(Empty);
+        assert_eq!(focused(&screen), Some(b.id()));
+        assert_eq!(fixtures::take(&log), ["b focus"]);
+        let mut builder = ScreenBuilder::new();
+        let _a = builder.view_cell(Probe::new("a", &log));
+        assert_eq!(focused(&builder.finish(Empty)), None);
+    }
+
+    #[test]
+    fn focus_change_unfocuses_before_focusing() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", &log));
+        let b = builder.view_cell(Probe::new("b", &log));
+        builder.initial_focus(&a);
+        let mut screen = builder.finish(Empty);
+        fixtures::take(&log);
+        assert!(screen.focus(&b));
+        assert_eq!(fixtures::take(&log), ["a unfocus", "b focus"]);
+        assert!(screen.focus(&b));
+        assert_eq!(fixtures::take(&log), Vec::<String>::new());
+    }
+
+    #[test]
+    fn focus_tag_finds_tagged_view() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.tagged_view_cell("a", Probe::new("a", &log));
+        let b = builder.tagged_view_cell("b", Probe::new("b", &log));
+        builder.initial_focus(&a);
+        let mut screen = builder.finish(Empty);
+        assert!(screen.focus_tag("b"));
+        assert_eq!(focused(&screen), Some(b.id()));
+        assert!(!screen.focus_tag("missing"));
+        assert_eq!(focused(&screen), Some(b.id()));
+    }
+
+    #[test]
+    fn tab_after_blur_focuses_first_view() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", &log));
+        let b = builder.view_cell(Probe::new("b", &log));
+        builder.initial_focus(&b);
+        let mut test = TestScreen::new(builder.finish(Empty), 20, 3);
+        fixtures::take(&log);
+        test.screen_mut().blur();
+        assert_eq!(focused(test.screen()), None);
+        assert_eq!(fixtures::take(&log), ["b unfocus"]);
+        test.press("Tab");
+        assert_eq!(focused(test.screen()), Some(a.id()));
+        test.screen_mut().blur();
+        test.press("S-Tab");
+        assert_eq!(focused(test.screen()), Some(b.id()));
+    }
+
+    #[test]
+    fn tab_order_follows_tab_index() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", &log)).tab_index(2);
+        let b = builder.view_cell(Probe::new("b", &log)).tab_index(1);
+        let c = builder.view_cell(Probe::new("c", &log));
+        let d = builder.view_cell(Probe::new("d", &log)).tab_index(1);
+        let mut test = TestScreen::new(builder.finish(Empty), 20, 3);
+        let mut order = Vec::new();
+        for _ in 0..5 {
+            test.press("Tab");
+            order.extend(focused(test.screen()));
+        }
+        assert_eq!(order, [c.id(), b.id(), d.id(), a.id(), c.id()]);
+    }
+
+    /// A screen with `a`, a focus group `g` of `g1`, `g2`, `g3`, and `b`, in tab order.
+    fn group_screen<'a>(log: &Log) -> (Screen<'a, Empty>, [ViewCell<'a>; 5]) {
+        let mut builder = ScreenBuilder::new();
+        let views = [
+            builder.view_cell(Probe::new("a", log)),
+            builder.view_cell(Probe::new("g1", log)).focus_group("g"),
+            builder.view_cell(Probe::new("g2", log)).focus_group("g"),
+            builder.view_cell(Probe::new("g3", log)).focus_group("g"),
+            builder.view_cell(Probe::new("b", log)),
+        ];
+        builder.initial_focus(&views[0]);
+        (builder.finish(Empty), views)
+    }
+
+    #[test]
+    fn tab_skips_rest_of_group() {
+        let log = Log::default();
+        let (screen, [a, g1, _g2, _g3, b]) = group_screen(&log);
+        let mut test = TestScreen::new(screen, 20, 3);
+        test.press("Tab");
+        assert_eq!(focused(test.screen()), Some(g1.id()));
+        test.press("Tab");
+        assert_eq!(focused(test.screen()), Some(b.id()));
+        test.press("S-Tab");
+        assert_eq!(focused(test.screen()), Some(g1.id()));
+        test.press("S-Tab");
+        assert_eq!(focused(test.screen()), Some(a.id()));
+    }
+
+    #[test]
+    fn arrow_keys_move_within_group() {
+        let log = Log::default();
+        let (screen, [a, g1, g2, g3, _b]) = group_screen(&log);
+        let mut test = TestScreen::new(screen, 20, 3);
+        fixtures::take(&log);
+        assert_eq!(test.press("Right"), EventResult::Handled);
+        assert_eq!(fixtures::take(&log), ["a key Right"]);
+        assert_eq!(focused(test.screen()), Some(a.id()));
+        test.press("Tab");
+        test.press("Right");
+        assert_eq!(focused(test.screen()), Some(g2.id()));
+        test.press("Down");
+        assert_eq!(focused(test.screen()), Some(g3.id()));
+        test.press("Right");
+        assert_eq!(focused(test.screen()), Some(g1.id()));
+        test.press("Left");
+        assert_eq!(focused(test.screen()), Some(g3.id()));
+        test.press("Up");
+        assert_eq!(focused(test.screen()), Some(g2.id()));
+        assert!(!fixtures::take(&log)
+            .iter()
+            .any(|entry| entry.contains("key")));
+    }
+
+    #[test]
+    fn group_remembers_last_focused_view() {
+        let log = Log::default();
+        let (screen, [_a, _g1, g2, _g3, b]) = group_screen(&log);
+        let mut test = TestScreen::new(screen, 20, 3);
+        test.press("Tab Right Tab");
+        assert_eq!(focused(test.screen()), Some(b.id()));
+        test.press("Tab Tab");
+        assert_eq!(focused(test.screen()), Some(g2.id()));
+        test.press("Tab S-Tab");
+        assert_eq!(focused(test.screen()), Some(g2.id()));
+        test.screen_mut().focus_first();
+        test.screen_mut().focus_next();
+        assert_eq!(focused(test.screen()), Some(g2.id()));
+    }
+
+    /// A screen with `a` and `b` on the top row and `c` and `d` on the bottom row of a 20x6 grid.
+    fn grid_screen<'a>(log: &Log) -> (TestScreen<'a, impl View + 'a>, [ViewCell<'a>; 4]) {
+        let mut builder = ScreenBuilder::new();
+        let [a, b, c, d] =
+            ["a", "b", "c", "d"].map(|name| builder.view_cell(Probe::new(name, log)));
+        let root = Stack::vertical((
+            Stack::horizontal((a.clone(), b.clone())),
+            Stack::horizontal((c.clone(), d.clone())),
+        ));
+        let test = TestScreen::new(builder.finish(root), 20, 6);
+        (test, [a, b, c, d])
+    }
+
+    /// Focus view `from` of a grid screen, then press `keys`, returning which view is focused.
+    fn focus_from(from: usize, keys: &str) -> Option<&'static str> {
+        let log = Log::default();
+        let (mut test, views) = grid_screen(&log);
+        test.screen_mut().focus(&views[from]);
+        test.render();
+        test.press(keys);
+        let focused = focused(test.screen())?;
+        let idx = views.iter().position(|view| view.id() == focused)?;
+        Some(["a", "b", "c", "d"][idx])
+    }
+
+    #[test]
+    fn alt_right_focuses_view_to_the_right() {
+        assert_eq!(focus_from(0, "M-Right"), Some("b"));
+        assert_eq!(focus_from(2, "M-Right"), Some("d"));
+    }
+
+    #[test]
+    fn alt_left_focuses_view_to_the_left() {
+        assert_eq!(focus_from(1, "M-Left"), Some("a"));
+        assert_eq!(focus_from(3, "M-Left"), Some("c"));
+    }
+
+    #[test]
+    fn alt_down_focuses_view_below() {
+        assert_eq!(focus_from(0, "M-Down"), Some("c"));
+        assert_eq!(focus_from(1, "M-Down"), Some("d"));
+    }
+
+    #[test]
+    fn alt_up_focuses_view_above() {
+        assert_eq!(focus_from(2, "M-Up"), Some("a"));
+        assert_eq!(focus_from(3, "M-Up"), Some("b"));
+    }
+
+    #[test]
+    fn focus_stays_without_view_in_direction() {
+        assert_eq!(focus_from(0, "M-Left"), Some("a"));
+        assert_eq!(focus_from(0, "M-Up"), Some("a"));
+        assert_eq!(focus_from(3, "M-Right"), Some("d"));
+        assert_eq!(focus_from(3, "M-Down"), Some("d"));
+    }
+
+    /// A screen with focusable `a` and non-focusable `b` side by side on the top row of a 20x4
+    /// screen, and nothing on the bottom row. The root view writes mouse events passing through it
+    /// into the log as `"root capture 1,2"` and `"root bubble 1,2"`.
+    fn mouse_screen<'a>(log: &Log) -> (TestScreen<'a, impl View + 'a>, [ViewCell<'a>; 2]) {
+        fn write(log: &Log, phase: &str, event: &Event) -> Propagation {
+            if let Event::Mouse(mouse_event) = event {
+                let (column, row) = (mouse_event.column, mouse_event.row);
+                log.lock()
+                    .unwrap()
+                    .push(format!("root {phase} {column},{row}"));
+            }
+            Propagation::Continue
+        }
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", log));
+        let b = builder.view_cell(Probe::new("b", log).unfocusable());
+        let (capture_log, bubble_log) = (log.clone(), log.clone());
+        let top = Stack::horizontal((a.clone(), b.clone())).prefers_size((20, 2));
+        let root = Stack::vertical((top, Empty))
+            .on_capture(move |event| write(&capture_log, "capture", event))
+            .on_bubble(move |event| write(&bubble_log, "bubble", event));
+        let test = TestScreen::new(builder.finish(root), 20, 4);
+        (test, [a, b])
+    }
+
+    #[test]
+    fn mouse_events_are_local_to_target_view() {
+        let log = Log::default();
+        let (mut test, _views) = mouse_screen(&log);
+        test.render();
+        let move_to = |column, row| {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Moved,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        test.send(move_to(3, 1));
+        test.send(move_to(13, 0));
+        assert_eq!(
+            fixtures::take(&log),
+            [
+                "root capture 3,1",
+                "a mouse 3,1",
+                "root bubble 3,1",
+                "root capture 13,0",
+                "b mouse 3,0",
+                "root bubble 13,0",
+            ],
+        );
+    }
+
+    #[test]
+    fn click_focuses_focusable_view() {
+        let log = Log::default();
+        let (mut test, [a, b]) = mouse_screen(&log);
+        test.click(2, 0);
+        assert_eq!(focused(test.screen()), Some(a.id()));
+        test.click(12, 0);
+        assert_eq!(focused(test.screen()), Some(a.id()));
+        test.click(12, 3);
+        assert_eq!(focused(test.screen()), Some(a.id()));
+        assert!(!b.lock().unwrap().is_focused);
+    }
+
+    #[test]
+    fn mouse_outside_views_reaches_root_view() {
+        let log = Log::default();
+        let (mut test, _views) = mouse_screen(&log);
+        assert_eq!(test.click(5, 3), EventResult::Ignored);
+        assert_eq!(
+            fixtures::take(&log),
+            ["root capture 5,3", "root bubble 5,3"].repeat(2),
+        );
+    }
+
+    /// An event handler writing key events into the log, e.g. `"outer capture Tab"`, and
+    /// returning `propagation`.
+    fn log_keys(
+        log: &Log,
+        name: &'static str,
+        propagation: Propagation,
+    ) -> impl Fn(&Event) -> Propagation {
+        let log = log.clone();
+        move |event| {
+            if let Event::Key(key_event) = event {
+                let chord = KeyChord::from(*key_event);
+                log.lock().unwrap().push(format!("{name} {chord}"));
+            }
+            propagation
+        }
+    }
+
+    #[test]
+    fn events_are_captured_and_bubbled_through_nested_stacks() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", &log));
+        builder.initial_focus(&a);
+        let inner = Stack::horizontal((a.clone(), Empty))
+            .on_capture(log_keys(&log, "inner capture", Propagation::Continue))
+            .on_bubble(log_keys(&log, "inner bubble", Propagation::Continue));
+        let root = Stack::vertical((inner, Empty))
+            .on_capture(log_keys(&log, "outer capture", Propagation::Continue))
+            .on_bubble(log_keys(&log, "outer bubble", Propagation::Continue));
+        let mut test = TestScreen::new(builder.finish(root), 20, 4);
+        fixtures::take(&log);
+        test.press("x");
+        assert_eq!(
+            fixtures::take(&log),
+            [
+                "outer capture x",
+                "inner capture x",
+                "a key x",
+                "inner bubble x",
+                "outer bubble x",
+            ],
+        );
+    }
+
+    #[test]
+    fn capture_stop_keeps_keys_from_view_and_keybindings() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", &log));
+        let _b = builder.view_cell(Probe::new("b", &log));
+        builder.initial_focus(&a);
+        let root = Stack::vertical((a.clone(), Empty))
+            .on_capture(log_keys(&log, "capture", Propagation::Stop))
+            .on_bubble(log_keys(&log, "bubble", Propagation::Continue));
+        let mut test = TestScreen::new(builder.finish(root), 20, 4);
+        fixtures::take(&log);
+        assert_eq!(test.press("x"), EventResult::Handled);
+        assert_eq!(test.press("Tab"), EventResult::Handled);
+        assert_eq!(fixtures::take(&log), ["capture x", "capture Tab"]);
+        assert_eq!(focused(test.screen()), Some(a.id()));
+    }
+
+    #[test]
+    fn bubble_handles_keys_ignored_by_target() {
+        let log = Log::default();
+        for (propagation, result) in [
+            (Propagation::Continue, EventResult::Ignored),
+            (Propagation::Stop, EventResult::Handled),
+        ] {
+            let mut builder = ScreenBuilder::new();
+            let a = builder.view_cell(Probe::new("a", &log).ignoring());
+            builder.initial_focus(&a);
+            let root = Stack::vertical((a.clone(), Empty)).on_bubble(log_keys(
+                &log,
+                "bubble",
+                propagation,
+            ));
+            let mut test = TestScreen::new(builder.finish(root), 20, 4);
+            fixtures::take(&log);
+            assert_eq!(test.press("x"), result);
+            assert_eq!(fixtures::take(&log), ["a key x", "bubble x"]);
+        }
+    }
+
+    /// A screen with views `a` and `b`, with `a` focused.
+    fn two_view_screen<'a>(a: Probe, b: Probe) -> (TestScreen<'a, Empty>, [ViewCell<'a>; 2]) {
+        let mut builder = ScreenBuilder::new();
+        let views = [builder.view_cell(a), builder.view_cell(b)];
+        builder.initial_focus(&views[0]);
+        (TestScreen::new(builder.finish(Empty), 20, 3), views)
+    }
+
+    #[test]
+    fn tab_can_be_rebound() {
+        let log = Log::default();
+        let (mut test, [a, b]) = two_view_screen(Probe::new("a", &log), Probe::new("b", &log));
+        let callback_log = log.clone();
+        test.screen_mut()
+            .bind("Tab", move |_| {
+                callback_log.lock().unwrap().push("Tab".into())
+            })
+            .unwrap();
+        test.screen_mut()
+            .bind_action("C-n", Action::FocusNext)
+            .unwrap();
+        fixtures::take(&log);
+        assert_eq!(test.press("Tab"), EventResult::Handled);
+        assert_eq!(fixtures::take(&log), ["Tab"]);
+        assert_eq!(focused(test.screen()), Some(a.id()));
+        assert_eq!(test.press("C-n"), EventResult::Handled);
+        assert_eq!(focused(test.screen()), Some(b.id()));
+    }
+
+    #[test]
+    fn unbound_tab_reaches_focused_view() {
+        let log = Log::default();
+        let (mut test, [a, _b]) =
+            two_view_screen(Probe::new("a", &log).ignoring(), Probe::new("b", &log));
+        test.screen_mut().unbind("Tab").unwrap();
+        fixtures::take(&log);
+        assert_eq!(test.press("Tab"), EventResult::Ignored);
+        assert_eq!(fixtures::take(&log), ["a key Tab"]);
+        assert_eq!(focused(test.screen()), Some(a.id()));
+    }
+
+    #[test]
+    fn fallback_runs_after_focused_view_ignores_keys() {
+        let log = Log::default();
+        for (probe, expected) in [
+            (Probe::new("a", &log), ["a key x"].as_slice()),
+            (Probe::new("a", &log).ignoring(), &["a key x", "fallback"]),
+        ] {
+            let (mut test, _views) = two_view_screen(probe, Probe::new("b", &log));
+            let callback_log = log.clone();
+            test.screen_mut()
+                .bind_fallback("x", move |_| {
+                    callback_log.lock().unwrap().push("fallback".into())
+                })
+                .unwrap();
+            fixtures::take(&log);
+            assert_eq!(test.press("x"), EventResult::Handled);
+            assert_eq!(fixtures::take(&log), expected);
+        }
+    }
+
+    #[test]
+    fn keys_report_whether_they_were_handled() {
+        let log = Log::default();
+        let (mut test, _views) =
+            two_view_screen(Probe::new("a", &log).ignoring(), Probe::new("b", &log));
+        test.screen_mut().bind("C-s", |_| ()).unwrap();
+        assert_eq!(test.press("C-s"), EventResult::Handled);
+        assert_eq!(test.press("Tab"), EventResult::Handled);
+        assert_eq!(test.press("x"), EventResult::Handled);
+        test.screen_mut().focus_first();
+        assert_eq!(test.press("x"), EventResult::Ignored);
+        // Right does not apply outside of a focus group, so `a` receives and ignores it.
+        fixtures::take(&log);
+        assert_eq!(test.press("Right"), EventResult::Ignored);
+        assert_eq!(fixtures::take(&log), ["a key Right"]);
+    }
+
+    #[test]
+    fn reentrant_callback_is_skipped() {
+        let log = Log::default();
+        let (mut test, _views) = two_view_screen(Probe::new("a", &log), Probe::new("b", &log));
+        test.screen_mut()
+            .bind("x", |screen| {
+                let key = Event::Key(KeyCode::Char('x').into());
+                assert_eq!(screen.handle_event(key), EventResult::Handled);
+            })
+            .unwrap();
+        fixtures::take(&log);
+        assert_eq!(test.press("x"), EventResult::Handled);
+        assert_eq!(fixtures::take(&log), ["a key x"]);
+    }
+
+    #[test]
+    fn ignored_mouse_moves_leave_screen_unchanged() {
+        let log = Log::default();
+        for (probe, needs_render) in [
+            (Probe::new("a", &log).ignoring(), false),
+            (Probe::new("a", &log), true),
+        ] {
+            let mut builder = ScreenBuilder::new();
+            let a = builder.view_cell(probe);
+            let mut test = TestScreen::new(builder.finish(a), 20, 3);
+            test.render();
+            test.send(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Moved,
+                column: 1,
+                row: 1,
+                modifiers: KeyModifiers::NONE,
+            }));
+            assert_eq!(test.screen().needs_render(), needs_render);
+        }
+    }
+
+    /// Renders its view only while `shown` is set.
+    struct Hideable<'a> {
+        shown: Arc<AtomicBool>,
+        view: ViewCell<'a>,
+    }
+
+    impl View for Hideable<'_> {
+        fn render(&self, frame: &mut Frame, area: Rect) {
+            if self.shown.load(Ordering::Relaxed) {
+                self.view.render(frame, area);
+            }
+        }
+
+        fn for_each_child<'s>(&'s self, f: &mut dyn FnMut(&'s dyn View)) {
+            f(&self.view);
+        }
+    }
+
+    #[test]
+    fn views_not_rendered_lose_their_area() {
+        let log = Log::default();
+        let mut builder = ScreenBuilder::new();
+        let a = builder.view_cell(Probe::new("a", &log));
+        let shown = Arc::new(AtomicBool::new(true));
+        let root = Hideable {
+            shown: shown.clone(),
+            view: a.clone(),
+        };
+        let mut test = TestScreen::new(builder.finish(root), 20, 3);
+        test.render();
+        assert_eq!(a.last_area(), Some(Rect::new(0, 0, 20, 3)));
+        shown.store(false, Ordering::Relaxed);
+        test.render();
+        assert_eq!(a.last_area(), None);
+        assert_eq!(test.click(1, 1), EventResult::Ignored);
+        assert_eq!(fixtures::take(&log), Vec::<String>::new());
+    }
+
+    #[test]
+    fn interrupted_keys_reach_focused_view() {
+        let (mut screen, input) = input_screen();
+        screen.bind("g g", |_| ()).unwrap();
+        let mut test = TestScreen::new(screen, 20, 3);
+        assert_eq!(test.press("g"), EventResult::Handled);
+        assert_eq!(text(&input), "");
+        assert_eq!(test.press("x"), EventResult::Handled);
+        assert_eq!(text(&input), "gx");
+        test.press("g g");
+        assert_eq!(text(&input), "gx");
+    }
+
+    #[test]
+    fn interrupted_leader_reaches_focused_view() {
+        let (mut screen, input) = input_screen();
+        screen.set_leader("Space").unwrap();
+        screen.bind("<leader> f", |_| ()).unwrap();
+        let mut test = TestScreen::new(screen, 20, 3);
+        test.type_text("a b");
+        assert_eq!(text(&input), "a b");
+    }
+
+    #[test]
+    fn interrupted_keys_run_bound_prefix() {
+        let (mut screen, input) = input_screen();
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        screen
+            .bind("g", move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap();
+        screen.bind("g g", |_| ()).unwrap();
+        let mut test = TestScreen::new(screen, 20, 3);
+        test.press("g x");
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+        assert_eq!(text(&input), "x");
+    }
+
+    #[test]
+    fn pending_keys_mark_screen_dirty() {
+        let mut screen = Screen::new(Empty);
+        screen.bind("C-x C-s", |_| ()).unwrap();
+        screen.set_key_timeout(Duration::ZERO);
+        let mut test = TestScreen::new(screen, 20, 3);
+        test.render();
+        test.press("C-x");
+        assert_eq!(test.screen().pending_keys(), ["C-x".parse().unwrap()]);
+        assert!(test.screen().needs_render());
+        test.render();
+        test.screen_mut().handle_key_timeout();
+        assert_eq!(test.screen().pending_keys(), []);
+        assert!(test.screen().needs_render());
+    }
+
+    #[test]
+    fn default_keys_hold_back_no_keys() {
+        let mut test = TestScreen::new(Screen::new(Empty), 20, 3);
+        assert_eq!(test.press("C-x"), EventResult::Ignored);
+        assert_eq!(test.screen().pending_keys(), []);
+    }
+
+    #[test]
+    fn timed_out_keys_reach_focused_view() {
+        let (mut screen, input) = input_screen();
+        screen.bind("g g", |_| ()).unwrap();
+        screen.set_key_timeout(Duration::ZERO);
+        let mut test = TestScreen::new(screen, 20, 3);
+        test.press("g");
+        assert_eq!(test.screen().pending_keys(), ["g".parse().unwrap()]);
+        test.screen_mut().handle_key_timeout();
+        assert_eq!(test.screen().pending_keys(), []);
+        assert_eq!(text(&input), "g");
+    }
+
+    /// Panics on every key.
+    struct Panicky;
+
+    impl MutView for Panicky {
+        fn render(&self, _frame: &mut Frame, _area: Rect, _is_focused: bool) {}
+
+        fn is_focusable(&self) -> bool {
+            true
+        }
+
+        fn on_key_event(
+            &mut self,
+            _key_event: KeyEvent,
+            _timer: &mut Option<Timer>,
+        ) -> EventResult {
+            panic!("view panicked");
         }
     }
+
+    #[test]
+    fn poisoned_view_is_reported_by_next_render() {
+        let mut builder = ScreenBuilder::new();
+        let view = builder.view_cell(Panicky);
+        builder.initial_focus(&view);
+        let mut screen = builder.finish(view);
+        let mut terminal = Terminal::new(TestBackend::new(20, 3)).unwrap();
+        screen.render(&mut terminal).unwrap();
+        let key = Event::Key(KeyCode::Char('a').into());
+        let panicked = panic::catch_unwind(AssertUnwindSafe(|| screen.handle_event(key.clone())));
+        assert!(panicked.is_err());
+        screen.render_state.dirty.store(false, Ordering::Relaxed);
+        assert_eq!(screen.handle_event(key), EventResult::Ignored);
+        assert!(screen.needs_render());
+        assert!(matches!(
+            screen.render(&mut terminal),
+            Err(Error::PoisonedLock)
+        ));
+    }
+
+    #[test]
+    fn theme_reaches_paragraphs_in_stacks() {
+        let paragraph = || Paragraph::new("text").block(Block::bordered());
+        let screen = Screen::new(Stack::vertical((paragraph(), paragraph().fg(Color::Red))));
+        let mut test = TestScreen::new(screen, 10, 6);
+        for theme in [Theme::dark(), Theme::light()] {
+            test.screen_mut().set_theme(theme);
+            let buffer = test.render();
+            assert_eq!(buffer[(0, 0)].style().fg, theme.muted.fg);
+            assert_eq!(buffer[(1, 1)].style().fg, theme.text.fg);
+            assert_eq!(buffer[(0, 3)].style().fg, Some(Color::Red));
+            assert_eq!(buffer[(1, 4)].style().fg, Some(Color::Red));
+        }
+    }
+
+    #[test]
+    fn default_theme_keeps_terminal_colors() {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(InputField::default().text("text"));
+        let screen = builder.finish(Stack::vertical((Paragraph::new("text"), input)));
+        let mut test = TestScreen::new(screen, 10, 2);
+        let buffer = test.render();
+        assert_eq!(buffer[(0, 0)].style().fg, Some(Color::Reset));
+        assert_eq!(buffer[(0, 1)].style().fg, Some(Color::Reset));
+        test.screen_mut().set_theme(Theme::dark());
+        let buffer = test.render();
+        assert_eq!(buffer[(0, 0)].style().fg, Some(Color::White));
+        assert_eq!(buffer[(0, 1)].style().fg, Some(Color::White));
+    }
+
+    #[test]
+    fn input_field_wraps_rows() {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(
+            InputField::default()
+                .text("hello wonderful world")
+                .cursor_at_end(),
+        );
+        builder.initial_focus(&input);
+        let mut test = TestScreen::new(builder.finish(input), 10, 3);
+        assert_eq!(test.plain(), "hello\nwonderful\nworld\n");
+        assert_eq!(test.cursor(), Some(Position::new(5, 2)));
+        test.press("C-a");
+        test.render();
+        assert_eq!(test.cursor(), Some(Position::new(0, 0)));
+    }
+
+    #[test]
+    fn input_field_caret_after_full_row_starts_next_row() {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(InputField::default().text("hello worl").cursor_at_end());
+        builder.initial_focus(&input);
+        let mut test = TestScreen::new(builder.finish(input), 10, 3);
+        assert_eq!(test.plain(), "hello worl\n\n\n");
+        assert_eq!(test.cursor(), Some(Position::new(0, 1)));
+        test.press("Left");
+        test.render();
+        assert_eq!(test.cursor(), Some(Position::new(9, 0)));
+        test.press("Right");
+        test.type_text("d!");
+        assert_eq!(test.plain(), "hello\nworld!\n\n");
+        assert_eq!(test.cursor(), Some(Position::new(6, 1)));
+    }
+
+    #[test]
+    fn input_field_scrolls_single_row() {
+        let mut builder = ScreenBuilder::new();
+        let input = builder.view_cell(InputField::default().text("hello world").cursor_at_end());
+        builder.initial_focus(&input);
+        let mut test = TestScreen::new(builder.finish(input), 8, 1);
+        assert_eq!(test.plain(), "o world\n");
+        assert_eq!(test.cursor(), Some(Position::new(7, 0)));
+    }
+
+    #[test]
+    fn zero_interval_is_raised() {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let mut builder = ScreenBuilder::new();
+        let ticker = builder.view_cell(Ticker(ticks.clone()));
+        let mut screen = builder.finish(ticker.clone());
+        let start = Instant::now();
+        screen.set_timer(&ticker, Some(Timer::Interval(Duration::ZERO)));
+        let deadline = screen.timer_deadline().unwrap();
+        assert!(deadline >= start + Timer::MIN_INTERVAL);
+        screen.handle_timers_at(deadline);
+        screen.handle_timers_at(deadline);
+        assert_eq!(ticks.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            screen.timer_deadline(),
+            Some(deadline + Timer::MIN_INTERVAL)
+        );
+    }
+
+    /// Blinks while focused, and pauses blinking for a second on each key.
+    struct Blinker;
+
+    impl Blinker {
+        const BLINK: Timer = Timer::Interval(Duration::from_millis(500));
+        const PAUSE: Timer = Timer::Once(Duration::from_secs(1));
+    }
+
+    impl MutView for Blinker {
+        fn render(&self, _frame: &mut Frame, _area: Rect, _is_focused: bool) {}
+
+        fn is_focusable(&self) -> bool {
+            true
+        }
+
+        fn on_focus(&mut self, timer: &mut Option<Timer>) {
+            *timer = Some(Self::BLINK);
+        }
+
+        fn on_unfocus(&mut self, timer: &mut Option<Timer>) {
+            *timer = None;
+        }
+
+        fn on_key_event(&mut self, _key_event: KeyEvent, timer: &mut Option<Timer>) -> EventResult {
+            *timer = Some(Self::PAUSE);
+            EventResult::Handled
+        }
+
+        fn on_tick(&mut self, _elapsed: Duration, timer: &mut Option<Timer>) {
+            *timer = Some(Self::BLINK);
+        }
+    }
+
+    #[test]
+    fn event_hooks_set_timers() {
+        let mut builder = ScreenBuilder::new();
+        let blinker = builder.view_cell(Blinker);
+        let mut screen = builder.finish(blinker.clone());
+        assert_eq!(screen.timer_deadline(), None);
+        let start = Instant::now();
+        screen.focus(&blinker);
+        let deadline = screen.timer_deadline().unwrap();
+        assert!(deadline >= start + Duration::from_millis(500));
+        assert!(deadline < start + Duration::from_secs(1));
+        screen.handle_event(Event::Key(KeyCode::Char('x').into()));
+        assert!(screen.timer_deadline().unwrap() >= start + Duration::from_secs(1));
+        screen.handle_timers_at(screen.timer_deadline().unwrap());
+        assert_eq!(screen.timers.len(), 1);
+        assert_eq!(screen.timers[0].timer, Blinker::BLINK);
+        screen.blur();
+        assert_eq!(screen.timer_deadline(), None);
+    }
+
+    #[test]
+    fn set_timer_through_handle() {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let mut builder = ScreenBuilder::new();
+        let ticker = builder.tagged_view_cell("ticker", Ticker(ticks.clone()));
+        let mut screen = builder.finish(ticker);
+        assert_eq!(screen.timer_deadline(), None);
+        assert!(screen
+            .handle()
+            .set_timer_tagged("ticker", Some(Timer::Once(Duration::ZERO))));
+        screen.process_messages();
+        let deadline = screen.timer_deadline().unwrap();
+        screen.handle_timers_at(deadline);
+        assert_eq!(ticks.load(Ordering::Relaxed), 1);
+        assert_eq!(screen.timer_deadline(), None);
+        assert!(!screen.set_timer_tagged("missing", None));
+    }
 }
@@ -0,0 +1,36 @@
+use domtui::{
+    testing::TestScreen,
+    views::{InputField, Screen, ScreenBuilder, Stack, View},
+};
+use ratatui::widgets::Block;
+
+const SNAPSHOTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots");
+
+/// Two input fields in a column, the first one focused.
+fn form() -> Screen<'static, impl View> {
+    let mut builder = ScreenBuilder::new();
+    let name = builder.view_cell(
+        InputField::default()
+            .placeholder("Name")
+            .block_focused(Block::bordered().title("Name"))
+            .block_unfocused(Block::bordered().title("Name")),
+    );
+    let email = builder.view_cell(
+        InputField::default()
+            .placeholder("Email")
+            .block_focused(Block::bordered().title("Email"))
+            .block_unfocused(Block::bordered().title("Email")),
+    );
+    builder.initial_focus(&name);
+    builder.finish(Stack::vertical((name, email)))
+}
+
+#[test]
+fn focus_and_input_field() {
+    let mut screen = TestScreen::new(form(), 20, 6);
+    screen.assert_plain_snapshot(format!("{SNAPSHOTS}/form_empty.txt"));
+    screen.run("type 'hello' <Tab> type 'me@example.com' <S-Tab> <C-a> <S-Right> <S-Right> <BS>");
+    screen.assert_plain_snapshot(format!("{SNAPSHOTS}/form_filled.txt"));
+    screen.run("<S-Right>");
+    screen.assert_styled_snapshot(format!("{SNAPSHOTS}/form_selection.txt"));
+}